@@ -1,5 +1,5 @@
 use aoc2020::d16::Field;
-use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use std::collections::HashSet;
 use std::iter::FromIterator;
 
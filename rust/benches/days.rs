@@ -0,0 +1,35 @@
+//! Benchmarks for every day registered in `aoc2020::DAYS`.
+//!
+//! Puzzle inputs are read from `inputs/dNN.txt` (e.g. `inputs/d01.txt`) or `$AOC2020_INPUTS`
+//! (see `aoc2020::inputs`), days without an input file are skipped.
+use aoc2020::inputs::{Inputs, ROOT_ENV_VAR};
+use aoc2020::solution::Part;
+use aoc2020::DAYS;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::env;
+use std::path::Path;
+
+pub fn bench_days(c: &mut Criterion) {
+    let mut group = c.benchmark_group("days");
+    // Some days take several seconds to solve (e.g. day 15 part 2)
+    group.sample_size(10);
+
+    let inputs = match env::var_os(ROOT_ENV_VAR) {
+        Some(_) => Inputs::from_env(),
+        None => Inputs::new(Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs")),
+    };
+    for day in DAYS {
+        let input = match inputs.read(day.day) {
+            Ok(Some(input)) => input,
+            _ => continue,
+        };
+        group.bench_function(day.name, |b| {
+            b.iter(|| day.solve(black_box(&input), &Part::ALL))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_days);
+criterion_main!(benches);
@@ -1,3 +1,6 @@
+use crate::parse::{Context, Located, ResultExt};
+use crate::solution::{NotImplemented, Solution};
+
 fn part_1() {
     todo!()
 }
@@ -6,18 +9,36 @@ fn part_2() {
     todo!()
 }
 
-fn parse_input(input: &str) -> Result<(), ParseError> {
+fn parse_input(input: &str) -> Result<(), Located<ParseError>> {
+    let ctx = Context::new(input);
     todo!()
 }
 
-pub fn run(input: &str) {
-    let parsed = parse_input(input).expect("unable to parse input");
-    println!("Part 1: {}", part_1(&parsed));
-    // println!("Part 2: {}", part_2(&parsed));
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Parsed = ();
+    type Answer1 = NotImplemented;
+    type Answer2 = NotImplemented;
+    type ParseError = Located<ParseError>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1(_: &Self::Parsed) -> Self::Answer1 {
+        NotImplemented
+    }
+
+    fn part_2(_: &Self::Parsed) -> Self::Answer2 {
+        NotImplemented
+    }
 }
 
 #[derive(PartialEq, PartialOrd, Eq, Ord, Debug)]
-enum ParseError {}
+pub enum ParseError {}
+
+impl_error!(parse ParseError);
 
 #[cfg(test)]
 mod tests {
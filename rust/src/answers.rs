@@ -0,0 +1,232 @@
+//! Known puzzle answers, used to check solutions for regressions.
+//!
+//! Answers are stored in a small subset of TOML with one table per day:
+//!
+//! ```text
+//! # Comments and blank lines are ignored
+//! [d01]
+//! part_1 = 514579
+//! part_2 = "241861950"
+//! ```
+//!
+//! Values may be integers or double quoted strings (for non-numeric answers such as day 21 part
+//! 2). Either part may be omitted.
+
+use crate::error::Error;
+use crate::solution::{Part, Status};
+use crate::str::split_once;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+#[derive(PartialEq, Eq, Debug, Default, Clone)]
+pub struct Answers(BTreeMap<(u8, Part), String>);
+
+impl Answers {
+    /// The known answer to `part` of `day`, if any.
+    pub fn get(&self, day: u8, part: Part) -> Option<&str> {
+        self.0.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, day: u8, part: Part, answer: String) -> Option<String> {
+        self.0.insert((day, part), answer)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Compare the outcome of solving `part` of `day` against the known answer.
+    pub fn verify(&self, day: u8, part: Part, status: &Status) -> Verdict {
+        match (status, self.get(day, part)) {
+            (Status::NotImplemented, _) => Verdict::Skipped,
+            (Status::Failed(e), _) => Verdict::Error(e.clone()),
+            (Status::Solved(_), None) => Verdict::Missing,
+            (Status::Solved(actual), Some(expected)) if actual == expected => Verdict::Pass,
+            (Status::Solved(actual), Some(expected)) => Verdict::Fail {
+                expected: expected.to_string(),
+                actual: actual.clone(),
+            },
+        }
+    }
+}
+
+/// Result of checking a single part against the known answer.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Verdict {
+    Pass,
+    Fail {
+        expected: String,
+        actual: String,
+    },
+    /// There is no known answer to compare against
+    Missing,
+    /// The part has not been implemented
+    Skipped,
+    /// The part could not be solved
+    Error(Error),
+}
+
+impl Verdict {
+    /// Index of the first character at which `expected` and `actual` differ, if this is a `Fail`.
+    pub fn mismatch(&self) -> Option<usize> {
+        match self {
+            Verdict::Fail { expected, actual } => Some(
+                expected
+                    .chars()
+                    .zip(actual.chars())
+                    .take_while(|(e, a)| e == a)
+                    .count(),
+            ),
+            _ => None,
+        }
+    }
+}
+
+/// Errors contain the (1 based) line number of the offending line.
+#[derive(PartialEq, Eq, Debug)]
+pub enum ParseError {
+    InvalidTable(usize),
+    InvalidKey(usize),
+    InvalidValue(usize),
+    MissingTable(usize),
+    DuplicateAnswer(usize),
+}
+
+impl FromStr for Answers {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut answers = Answers::default();
+        let mut day = None;
+        for (i, line) in s.lines().enumerate() {
+            let n = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(table) = line.strip_prefix('[') {
+                day = Some(parse_table(table).ok_or(ParseError::InvalidTable(n))?);
+                continue;
+            }
+            let day = day.ok_or(ParseError::MissingTable(n))?;
+            let (key, value) = split_once(line, "=").ok_or(ParseError::InvalidKey(n))?;
+            let part = match key.trim() {
+                "part_1" => Part::One,
+                "part_2" => Part::Two,
+                _ => return Err(ParseError::InvalidKey(n)),
+            };
+            let value = parse_value(value.trim()).ok_or(ParseError::InvalidValue(n))?;
+            if answers.insert(day, part, value).is_some() {
+                return Err(ParseError::DuplicateAnswer(n));
+            }
+        }
+        Ok(answers)
+    }
+}
+
+/// `table` is the remainder of a `[dNN]` line after the opening `[`.
+fn parse_table(table: &str) -> Option<u8> {
+    table
+        .strip_suffix(']')?
+        .trim()
+        .strip_prefix('d')?
+        .parse()
+        .ok()
+}
+
+fn parse_value(value: &str) -> Option<String> {
+    // Strip trailing comments outside of strings
+    if let Some(quoted) = value.strip_prefix('"') {
+        let (s, rest) = split_once(quoted, "\"")?;
+        let rest = rest.trim();
+        if rest.is_empty() || rest.starts_with('#') {
+            Some(s.to_string())
+        } else {
+            None
+        }
+    } else {
+        let value = value.split('#').next().unwrap().trim();
+        value.parse::<i64>().ok().map(|_| value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_answers() {
+        let answers: Answers = "# answers
+[d01]
+part_1 = 514579
+part_2 = \"241861950\" # quoted
+
+[d21]
+part_2 = \"mxmxvkd,sqjhc,fvjkl\"
+"
+        .parse()
+        .unwrap();
+        assert_eq!(answers.get(1, Part::One), Some("514579"));
+        assert_eq!(answers.get(1, Part::Two), Some("241861950"));
+        assert_eq!(answers.get(21, Part::One), None);
+        assert_eq!(answers.get(21, Part::Two), Some("mxmxvkd,sqjhc,fvjkl"));
+        assert_eq!(answers.get(2, Part::One), None);
+    }
+
+    #[test]
+    fn verify_answers() {
+        let answers: Answers = "[d01]\npart_1 = 514579".parse().unwrap();
+        let solved = |s: &str| Status::Solved(s.to_string());
+        assert_eq!(
+            answers.verify(1, Part::One, &solved("514579")),
+            Verdict::Pass
+        );
+        let fail = answers.verify(1, Part::One, &solved("514779"));
+        assert_eq!(
+            fail,
+            Verdict::Fail {
+                expected: "514579".to_string(),
+                actual: "514779".to_string()
+            }
+        );
+        assert_eq!(fail.mismatch(), Some(3));
+        assert_eq!(
+            answers.verify(1, Part::One, &solved("51457")).mismatch(),
+            Some(5)
+        );
+        assert_eq!(answers.verify(1, Part::Two, &solved("1")), Verdict::Missing);
+        assert_eq!(
+            answers.verify(1, Part::Two, &Status::NotImplemented),
+            Verdict::Skipped
+        );
+        let e = Error::solve("NotFound");
+        assert_eq!(
+            answers.verify(1, Part::One, &Status::Failed(e.clone())),
+            Verdict::Error(e)
+        );
+    }
+
+    #[test]
+    fn parse_answers_invalid() {
+        assert_eq!(
+            "part_1 = 1".parse::<Answers>(),
+            Err(ParseError::MissingTable(1))
+        );
+        assert_eq!(
+            "[day1]".parse::<Answers>(),
+            Err(ParseError::InvalidTable(1))
+        );
+        assert_eq!(
+            "[d01]\npart_3 = 1".parse::<Answers>(),
+            Err(ParseError::InvalidKey(2))
+        );
+        assert_eq!(
+            "[d01]\npart_1 = abc".parse::<Answers>(),
+            Err(ParseError::InvalidValue(2))
+        );
+        assert_eq!(
+            "[d01]\npart_1 = 1\npart_1 = 2".parse::<Answers>(),
+            Err(ParseError::DuplicateAnswer(3))
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! Sparse cellular automata on unbounded grids.
+//!
+//! Only the *active* cells are tracked, so the grid can grow in any direction without being
+//! resized and inactive space costs nothing. The shape of the grid is determined by the
+//! coordinate type through `Neighbourhood` (e.g. `Point<N>` for an `N` dimensional square grid,
+//! or a hexagonal coordinate), and the evolution of each cell by a life-like `Rule`:
+//!
+//! ```
+//! use aoc2020::automaton::{Automaton, Point};
+//!
+//! // A "blinker" oscillates between horizontal and vertical with period 2
+//! let blinker = [Point([0, 1]), Point([1, 1]), Point([2, 1])];
+//! let mut life = Automaton::new(blinker.iter().copied(), "B3/S23".parse().unwrap());
+//! life.step();
+//! assert!(life.is_active(&Point([1, 0])) && life.is_active(&Point([1, 2])));
+//! assert!(!life.is_active(&Point([0, 1])));
+//! life.step();
+//! assert_eq!(life.len(), 3);
+//! assert!(life.is_active(&Point([0, 1])));
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::str::FromStr;
+
+/// A cell coordinate which knows the coordinates of its neighbours.
+pub trait Neighbourhood: Copy + Eq + Hash {
+    /// Coordinates of all neighbouring cells, excluding `self`.
+    fn neighbours(self) -> impl Iterator<Item = Self>;
+}
+
+/// Coordinate in an `N` dimensional square grid, where each cell has the `3^N - 1` neighbours
+/// which differ by at most 1 in every dimension.
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Hash, Clone, Copy)]
+pub struct Point<const N: usize>(pub [i64; N]);
+
+impl<const N: usize> Point<N> {
+    /// The same point in `M` dimensions, padded with `0`s (or truncated if `M < N`).
+    pub fn embed<const M: usize>(self) -> Point<M> {
+        let mut p = [0; M];
+        for (d, x) in p.iter_mut().zip(&self.0) {
+            *d = *x;
+        }
+        Point(p)
+    }
+}
+
+impl<const N: usize> Neighbourhood for Point<N> {
+    fn neighbours(self) -> impl Iterator<Item = Self> {
+        // Each offset in `{-1, 0, 1}^N` is the base 3 representation of an integer in `0..3^N`
+        (0..3_usize.pow(N as u32))
+            .map(move |mut i| {
+                let mut p = self.0;
+                for x in p.iter_mut() {
+                    *x += (i % 3) as i64 - 1;
+                    i /= 3;
+                }
+                Point(p)
+            })
+            .filter(move |p| *p != self)
+    }
+}
+
+/// Life-like transition rule, determined by the number of active neighbours of a cell:
+/// - An inactive cell becomes active if the count is in `birth`
+/// - An active cell remains active if the count is in `survival`
+/// - Otherwise the cell is (or becomes) inactive
+///
+/// Rules can be parsed from the standard `B<birth>/S<survival>` notation for counts < 10 e.g.
+/// Conway's Game of Life is `B3/S23`.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Rule {
+    birth: Vec<usize>,
+    survival: Vec<usize>,
+}
+
+impl Rule {
+    /// Births with `0` active neighbours are ignored, as they would require activating the
+    /// infinitely many cells which are not adjacent to any active cell.
+    pub fn new(birth: &[usize], survival: &[usize]) -> Self {
+        Rule {
+            birth: birth.iter().copied().filter(|&n| n > 0).collect(),
+            survival: survival.to_vec(),
+        }
+    }
+
+    /// Whether a cell is active after a step, given whether it is currently `active` and the
+    /// number of currently active neighbours.
+    pub fn apply(&self, active: bool, active_neighbours: usize) -> bool {
+        if active {
+            self.survival.contains(&active_neighbours)
+        } else {
+            self.birth.contains(&active_neighbours)
+        }
+    }
+}
+
+impl FromStr for Rule {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let counts = |s: &str| {
+            s.chars()
+                .map(|c| c.to_digit(10).map(|d| d as usize))
+                .collect::<Option<Vec<_>>>()
+        };
+        let mut parts = s.split('/');
+        match (
+            parts.next().and_then(|b| b.strip_prefix('B')),
+            parts.next().and_then(|s| s.strip_prefix('S')),
+            parts.next(),
+        ) {
+            (Some(b), Some(s), None) => match (counts(b), counts(s)) {
+                (Some(b), Some(s)) => Ok(Rule::new(&b, &s)),
+                _ => Err("Was not Rule"),
+            },
+            _ => Err("Was not Rule"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Automaton<C> {
+    active: HashSet<C>,
+    rule: Rule,
+}
+
+impl<C: Neighbourhood> Automaton<C> {
+    pub fn new(active: impl IntoIterator<Item = C>, rule: Rule) -> Self {
+        Automaton {
+            active: active.into_iter().collect(),
+            rule,
+        }
+    }
+
+    pub fn active(&self) -> &HashSet<C> {
+        &self.active
+    }
+
+    pub fn is_active(&self, c: &C) -> bool {
+        self.active.contains(c)
+    }
+
+    /// Number of active cells.
+    pub fn len(&self) -> usize {
+        self.active.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    /// Apply the rule to every cell simultaneously.
+    ///
+    /// Only active cells and their neighbours can change state, so the number of active
+    /// neighbours is counted for just those cells by having each active cell "visit" its
+    /// neighbours.
+    pub fn step(&mut self) {
+        let mut counts: HashMap<C, usize> = HashMap::with_capacity(self.active.len() * 2);
+        for c in &self.active {
+            for n in c.neighbours() {
+                *counts.entry(n).or_insert(0) += 1;
+            }
+        }
+        let mut next: HashSet<C> = counts
+            .iter()
+            .filter(|(c, &n)| self.rule.apply(self.active.contains(c), n))
+            .map(|(c, _)| *c)
+            .collect();
+        // Active cells without any active neighbours are not visited above
+        if self.rule.apply(true, 0) {
+            next.extend(self.active.iter().filter(|c| !counts.contains_key(c)));
+        }
+        self.active = next;
+    }
+
+    /// Apply `steps` steps, returning the number of active cells afterwards.
+    pub fn run(&mut self, steps: usize) -> usize {
+        for _ in 0..steps {
+            self.step();
+        }
+        self.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn point_neighbours() {
+        let neighbours: HashSet<_> = Point([0, 0]).neighbours().collect();
+        assert_eq!(neighbours.len(), 8);
+        assert!(!neighbours.contains(&Point([0, 0])));
+        assert!(neighbours.contains(&Point([-1, 1])));
+        assert_eq!(Point([5, 5, 5]).neighbours().count(), 26);
+        assert_eq!(Point([0; 4]).neighbours().count(), 80);
+        assert!(Point([1, 2, 3])
+            .neighbours()
+            .all(|Point(p)| p.iter().zip(&[1, 2, 3]).all(|(a, b)| (a - b).abs() <= 1)));
+    }
+
+    #[test]
+    fn point_embed() {
+        assert_eq!(Point([1, 2]).embed::<4>(), Point([1, 2, 0, 0]));
+        assert_eq!(Point([1, 2, 3]).embed::<2>(), Point([1, 2]));
+    }
+
+    #[test]
+    fn parse_rule() {
+        assert_eq!("B3/S23".parse(), Ok(Rule::new(&[3], &[2, 3])));
+        assert_eq!("B/S".parse(), Ok(Rule::new(&[], &[])));
+        assert_eq!("B03/S0".parse(), Ok(Rule::new(&[3], &[0])));
+        assert_eq!("S23/B3".parse::<Rule>(), Err("Was not Rule"));
+        assert_eq!("B3/S2x".parse::<Rule>(), Err("Was not Rule"));
+        assert_eq!("B3/S23/".parse::<Rule>(), Err("Was not Rule"));
+    }
+
+    #[test]
+    fn glider() {
+        // Moves 1 cell diagonally every 4 steps
+        let glider = [[1, 0], [2, 1], [0, 2], [1, 2], [2, 2]];
+        let mut life = Automaton::new(glider.iter().map(|&p| Point(p)), Rule::new(&[3], &[2, 3]));
+        assert_eq!(life.run(4), 5);
+        let expected: HashSet<_> = glider.iter().map(|&[x, y]| Point([x + 1, y + 1])).collect();
+        assert_eq!(life.active(), &expected);
+    }
+
+    #[test]
+    fn isolated_survival() {
+        let mut automaton = Automaton::new(vec![Point([0]), Point([10])], Rule::new(&[], &[0]));
+        automaton.step();
+        assert_eq!(automaton.len(), 2);
+        let mut automaton = Automaton::new(vec![Point([0])], Rule::new(&[], &[1]));
+        automaton.step();
+        assert!(automaton.is_empty());
+    }
+}
@@ -0,0 +1,402 @@
+//! Command line argument parsing for the `aoc2020` binary.
+
+use crate::output::{Format, Render};
+use crate::repl::Precedence;
+use aoc2020::solution::{Part, CALENDAR};
+use aoc2020::DAYS;
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const USAGE: &str = "\
+Usage: aoc2020 <command> [options]
+
+Commands:
+    run <days...>      Solve the given days and print the answers
+    bench <days...>    Time solving the given days
+    check [days...]    Compare answers against an answers file, exiting with a non-zero status
+                       on mismatch. Checks every implemented day if no days are given
+    visualise <days...>
+                       Record each generation of the simulations of the given days (11, 17,
+                       23 and 24) or the assembled image of day 20 and render them as selected
+                       by --render
+    repl               Evaluate day 18 expressions typed a line at a time, showing the value,
+                       postfix form and syntax tree of each
+    list               Show which days and parts have been implemented
+    help               Show this message
+
+Days are given as numbers (1), inclusive ranges (1-10) or `all`. `aoc2020 <day>` is
+equivalent to `aoc2020 run <day>`.
+
+Inputs are read from <root>/dNN.txt e.g. inputs/d01.txt. The root is `inputs` in the current
+directory unless overridden by --input or the AOC2020_INPUTS environment variable. When running a
+single day without an input file, input is read from stdin instead.
+
+Options:
+    --part <1|2>           Only solve the given part
+    --input <path>         Read input from a file (single day only) or from <path>/dNN.txt
+    --answers <file>       Answers file used by `check` [default: answers.toml]
+    --iterations <n>       Number of times `bench` solves each day [default: 10]
+    --timings              Print a table of parse and solve times after `run`
+    --format <format>      Output format of `run`: text, json or csv [default: text].
+                           json and csv emit day, part, answer and duration records
+    --render <render>      How `visualise` renders generations: text, replay (an animation in
+                           the terminal), pbm, pgm or ppm (an image per generation)
+                           [default: text]
+    --output <dir>         Directory `visualise` writes images to [default: frames]
+    --delay <ms>           Time between generations of a replay [default: 100]
+    --scale <n>            Width and height in pixels of each cell of an image [default: 4]
+    --precedence <rules>   Operator precedence `repl` starts with: part1, part2 or standard
+                           [default: standard]
+";
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Command {
+    Run,
+    Bench,
+    Check,
+    Visualise,
+    Repl,
+    List,
+    Help,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub struct Args {
+    pub command: Command,
+    pub days: Vec<u8>,
+    pub parts: Vec<Part>,
+    pub input: Option<PathBuf>,
+    pub answers: PathBuf,
+    pub iterations: usize,
+    pub timings: bool,
+    pub format: Format,
+    pub render: Render,
+    pub output: PathBuf,
+    pub delay: Duration,
+    pub scale: usize,
+    pub precedence: Precedence,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum ArgsError {
+    InvalidCommand(String),
+    InvalidDay(String),
+    InvalidOption(String),
+    InvalidValue(&'static str, String),
+    MissingValue(&'static str),
+    MissingDays,
+    /// A single input file was given for multiple days
+    AmbiguousInput,
+}
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgsError::InvalidCommand(s) => write!(f, "unknown command `{}`", s),
+            ArgsError::InvalidDay(s) => write!(
+                f,
+                "invalid day `{}`: expected a day between {} and {}, a range or `all`",
+                s,
+                CALENDAR.start(),
+                CALENDAR.end()
+            ),
+            ArgsError::InvalidOption(s) => write!(f, "unknown option `{}`", s),
+            ArgsError::InvalidValue(opt, s) => write!(f, "invalid value `{}` for {}", s, opt),
+            ArgsError::MissingValue(opt) => write!(f, "{} requires a value", opt),
+            ArgsError::MissingDays => write!(f, "no days given"),
+            ArgsError::AmbiguousInput => write!(
+                f,
+                "multiple days given: --input must be a directory containing dNN.txt files"
+            ),
+        }
+    }
+}
+
+impl Args {
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, ArgsError> {
+        let mut args = args.into_iter();
+        let mut parsed = Args {
+            command: Command::Help,
+            days: vec![],
+            parts: Part::ALL.to_vec(),
+            input: None,
+            answers: PathBuf::from("answers.toml"),
+            iterations: 10,
+            timings: false,
+            format: Format::Text,
+            render: Render::Text,
+            output: PathBuf::from("frames"),
+            delay: Duration::from_millis(100),
+            scale: 4,
+            precedence: Precedence::Standard,
+        };
+        let mut positional = vec![];
+
+        match args.next().as_deref() {
+            None | Some("help") | Some("-h") | Some("--help") => return Ok(parsed),
+            Some("run") => parsed.command = Command::Run,
+            Some("bench") => parsed.command = Command::Bench,
+            Some("check") => parsed.command = Command::Check,
+            Some("visualise") => parsed.command = Command::Visualise,
+            Some("repl") => parsed.command = Command::Repl,
+            Some("list") => parsed.command = Command::List,
+            // Backwards compatible `aoc2020 <day>`
+            Some(day) if day.parse::<u8>().is_ok() => {
+                parsed.command = Command::Run;
+                positional.push(day.to_string());
+            }
+            Some(s) => return Err(ArgsError::InvalidCommand(s.to_string())),
+        }
+
+        while let Some(arg) = args.next() {
+            let mut value = |opt| args.next().ok_or(ArgsError::MissingValue(opt));
+            match arg.as_str() {
+                "--part" => {
+                    let v = value("--part")?;
+                    let part = v
+                        .parse()
+                        .map_err(|_| ArgsError::InvalidValue("--part", v))?;
+                    parsed.parts = vec![part];
+                }
+                "--input" => parsed.input = Some(PathBuf::from(value("--input")?)),
+                "--answers" => parsed.answers = PathBuf::from(value("--answers")?),
+                "--iterations" => {
+                    let v = value("--iterations")?;
+                    parsed.iterations = v
+                        .parse()
+                        .ok()
+                        .filter(|&n| n > 0)
+                        .ok_or(ArgsError::InvalidValue("--iterations", v))?;
+                }
+                "--timings" => parsed.timings = true,
+                "--format" => {
+                    let v = value("--format")?;
+                    parsed.format = v
+                        .parse()
+                        .map_err(|_| ArgsError::InvalidValue("--format", v))?;
+                }
+                "--render" => {
+                    let v = value("--render")?;
+                    parsed.render = v
+                        .parse()
+                        .map_err(|_| ArgsError::InvalidValue("--render", v))?;
+                }
+                "--output" => parsed.output = PathBuf::from(value("--output")?),
+                "--delay" => {
+                    let v = value("--delay")?;
+                    let ms = v
+                        .parse()
+                        .map_err(|_| ArgsError::InvalidValue("--delay", v))?;
+                    parsed.delay = Duration::from_millis(ms);
+                }
+                "--scale" => {
+                    let v = value("--scale")?;
+                    parsed.scale = v
+                        .parse()
+                        .ok()
+                        .filter(|&n| n > 0)
+                        .ok_or(ArgsError::InvalidValue("--scale", v))?;
+                }
+                "--precedence" => {
+                    let v = value("--precedence")?;
+                    parsed.precedence = v
+                        .parse()
+                        .map_err(|_| ArgsError::InvalidValue("--precedence", v))?;
+                }
+                s if s.starts_with("--") => return Err(ArgsError::InvalidOption(arg)),
+                _ => positional.push(arg),
+            }
+        }
+
+        // Commands which don't take any days
+        if matches!(parsed.command, Command::List | Command::Repl) {
+            if let Some(arg) = positional.into_iter().next() {
+                return Err(ArgsError::InvalidDay(arg));
+            }
+            return Ok(parsed);
+        }
+
+        for spec in &positional {
+            parsed.days.extend(parse_days(spec)?);
+        }
+        if parsed.command == Command::Check && positional.is_empty() {
+            parsed.days = DAYS.iter().map(|d| d.day).collect();
+        }
+        parsed.days.sort_unstable();
+        parsed.days.dedup();
+        if parsed.days.is_empty() {
+            return Err(ArgsError::MissingDays);
+        }
+        if parsed.days.len() > 1 && parsed.input.as_ref().is_some_and(|p| !p.is_dir()) {
+            return Err(ArgsError::AmbiguousInput);
+        }
+        Ok(parsed)
+    }
+}
+
+/// Parse a day specification: a single day (`1`), an inclusive range (`1-10`) or `all`.
+fn parse_days(spec: &str) -> Result<Vec<u8>, ArgsError> {
+    let invalid = || ArgsError::InvalidDay(spec.to_string());
+    let day = |s: &str| {
+        s.parse::<u8>()
+            .ok()
+            .filter(|d| CALENDAR.contains(d))
+            .ok_or_else(invalid)
+    };
+    if spec == "all" {
+        return Ok(CALENDAR.collect());
+    }
+    let mut bounds = spec.splitn(2, '-');
+    let start = day(bounds.next().unwrap())?;
+    match bounds.next() {
+        Some(end) => {
+            let end = day(end)?;
+            if start > end {
+                return Err(invalid());
+            }
+            Ok((start..=end).collect())
+        }
+        None => Ok(vec![start]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc2020::visualise::ImageFormat;
+
+    fn parse(args: &str) -> Result<Args, ArgsError> {
+        Args::parse(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn parse_days_specs() {
+        assert_eq!(parse_days("1"), Ok(vec![1]));
+        assert_eq!(parse_days("3-5"), Ok(vec![3, 4, 5]));
+        assert_eq!(parse_days("all"), Ok(CALENDAR.collect()));
+        for spec in &["0", "26", "5-3", "1-", "-1", "a"] {
+            assert_eq!(
+                parse_days(spec),
+                Err(ArgsError::InvalidDay(spec.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn parse_run() {
+        let args = parse("run 3 --part 2 --input d03.txt").unwrap();
+        assert_eq!(args.command, Command::Run);
+        assert_eq!(args.days, vec![3]);
+        assert_eq!(args.parts, vec![Part::Two]);
+        assert_eq!(args.input, Some(PathBuf::from("d03.txt")));
+        assert!(!args.timings);
+    }
+
+    #[test]
+    fn parse_timings() {
+        let args = parse("run all --input . --timings").unwrap();
+        assert_eq!(args.days, CALENDAR.collect::<Vec<_>>());
+        assert!(args.timings);
+    }
+
+    #[test]
+    fn parse_format() {
+        assert_eq!(parse("run 1").unwrap().format, Format::Text);
+        assert_eq!(parse("run 1 --format csv").unwrap().format, Format::Csv);
+        assert_eq!(
+            parse("run 1 --format xml"),
+            Err(ArgsError::InvalidValue("--format", "xml".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_visualise() {
+        let args = parse("visualise 11 --part 1").unwrap();
+        assert_eq!(args.command, Command::Visualise);
+        assert_eq!(args.render, Render::Text);
+        assert_eq!(args.output, PathBuf::from("frames"));
+        assert_eq!(args.delay, Duration::from_millis(100));
+        assert_eq!(args.scale, 4);
+
+        let args = parse("visualise 17 --render ppm --output out --scale 2").unwrap();
+        assert_eq!(args.render, Render::Image(ImageFormat::Ppm));
+        assert_eq!(args.output, PathBuf::from("out"));
+        assert_eq!(args.scale, 2);
+        let args = parse("visualise 24 --render replay --delay 20").unwrap();
+        assert_eq!(args.render, Render::Replay);
+        assert_eq!(args.delay, Duration::from_millis(20));
+
+        assert_eq!(
+            parse("visualise 11 --render gif"),
+            Err(ArgsError::InvalidValue("--render", "gif".to_string()))
+        );
+        assert_eq!(
+            parse("visualise 11 --scale 0"),
+            Err(ArgsError::InvalidValue("--scale", "0".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_repl() {
+        let args = parse("repl").unwrap();
+        assert_eq!(args.command, Command::Repl);
+        assert_eq!(args.precedence, Precedence::Standard);
+        assert_eq!(
+            parse("repl --precedence part1").unwrap().precedence,
+            Precedence::Part1
+        );
+        assert_eq!(
+            parse("repl --precedence 3"),
+            Err(ArgsError::InvalidValue("--precedence", "3".to_string()))
+        );
+        assert_eq!(
+            parse("repl 18"),
+            Err(ArgsError::InvalidDay("18".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_bare_day() {
+        let args = parse("1").unwrap();
+        assert_eq!(args.command, Command::Run);
+        assert_eq!(args.days, vec![1]);
+        assert_eq!(args.parts, Part::ALL.to_vec());
+    }
+
+    #[test]
+    fn parse_multiple_days() {
+        let args = parse("check 5-7 1 6 --input .").unwrap();
+        assert_eq!(args.command, Command::Check);
+        assert_eq!(args.days, vec![1, 5, 6, 7]);
+        assert_eq!(parse("run 1 2").unwrap().days, vec![1, 2]);
+        assert_eq!(
+            parse("run 1 2 --input d01.txt"),
+            Err(ArgsError::AmbiguousInput)
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(parse("").unwrap().command, Command::Help);
+        assert_eq!(
+            parse("go 1"),
+            Err(ArgsError::InvalidCommand("go".to_string()))
+        );
+        assert_eq!(parse("run"), Err(ArgsError::MissingDays));
+        assert_eq!(parse("check").unwrap().days.len(), DAYS.len());
+        assert_eq!(
+            parse("run 1 --part"),
+            Err(ArgsError::MissingValue("--part"))
+        );
+        assert_eq!(
+            parse("run 1 --part 3"),
+            Err(ArgsError::InvalidValue("--part", "3".to_string()))
+        );
+        assert_eq!(
+            parse("run 1 --verbose"),
+            Err(ArgsError::InvalidOption("--verbose".to_string()))
+        );
+        assert_eq!(parse("list 1"), Err(ArgsError::InvalidDay("1".to_string())));
+    }
+}
@@ -1,42 +1,72 @@
+use crate::parse::{Context, Located, ResultExt};
+use crate::solution::Solution;
 use itertools::Itertools;
 
 const TARGET: i32 = 2020;
 
-pub fn run(input: &str) {
-    let entries = parse_input(input);
-    println!("Part 1: {}", part_1(&entries));
-    println!("Part 2: {}", part_2(&entries));
-}
-
-fn part_1(entries: &[i32]) -> i32 {
+/// Returns `Err` if there are no pairs that sum to 2020.
+fn part_1(entries: &[i32]) -> Result<i32, Error> {
     entries
         .iter()
         .combinations(2)
         .find(|c| c.iter().copied().sum::<i32>() == TARGET)
-        .expect("no pairs that sum to 2020")
-        .iter()
-        .copied()
-        .product()
+        .map(|c| c.iter().copied().product())
+        .ok_or(Error::NotFound)
 }
 
-fn part_2(entries: &[i32]) -> i32 {
+/// Returns `Err` if there are no triples that sum to 2020.
+fn part_2(entries: &[i32]) -> Result<i32, Error> {
     entries
         .iter()
         .combinations(3)
         .find(|c| c.iter().copied().sum::<i32>() == TARGET)
-        .expect("no triples that sum to 2020")
-        .iter()
-        .copied()
-        .product()
+        .map(|c| c.iter().copied().product())
+        .ok_or(Error::NotFound)
 }
 
-fn parse_input(input: &str) -> Vec<i32> {
+fn parse_input(input: &str) -> Result<Vec<i32>, Located<ParseError>> {
+    let ctx = Context::new(input);
     input
         .lines()
-        .map(|s| s.parse().expect("could not parse input to integers"))
+        .map(|s| s.parse().map_err(ParseError::InvalidEntry).at(&ctx, s))
         .collect()
 }
 
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Parsed = Vec<i32>;
+    type Answer1 = Result<i32, Error>;
+    type Answer2 = Result<i32, Error>;
+    type ParseError = Located<ParseError>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1(entries: &Self::Parsed) -> Self::Answer1 {
+        part_1(entries)
+    }
+
+    fn part_2(entries: &Self::Parsed) -> Self::Answer2 {
+        part_2(entries)
+    }
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum ParseError {
+    InvalidEntry(std::num::ParseIntError),
+}
+
+impl_error!(parse ParseError);
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum Error {
+    NotFound,
+}
+
+impl_error!(solve Error);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -44,18 +74,21 @@ mod tests {
     #[test]
     fn parse_input_example() {
         let input = "1721\n979\n366\n299\n675\n1456";
-        assert_eq!(parse_input(input), [1721, 979, 366, 299, 675, 1456]);
+        assert_eq!(
+            parse_input(input).unwrap(),
+            [1721, 979, 366, 299, 675, 1456]
+        );
     }
 
     #[test]
     fn part_1_example() {
         let entries = [1721, 979, 366, 299, 675, 1456];
-        assert_eq!(part_1(&entries), 514579);
+        assert_eq!(part_1(&entries), Ok(514579));
     }
 
     #[test]
     fn part_2_example() {
         let entries = [1721, 979, 366, 299, 675, 1456];
-        assert_eq!(part_2(&entries), 241861950);
+        assert_eq!(part_2(&entries), Ok(241861950));
     }
 }
@@ -1,74 +1,61 @@
-use std::fmt;
+use crate::grid::{self, Grid};
+use crate::parse::Located;
+use crate::solution::Solution;
 
-pub fn run(input: &str) {
-    let grid = parse_input(input).expect("unable to parse input");
-    println!("Part 1: {}", part_1(&grid));
-    println!("Part 2: {}", part_2(&grid));
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Parsed = Grid<Square>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+    type ParseError = Located<grid::ParseError>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1(grid: &Self::Parsed) -> Self::Answer1 {
+        part_1(grid)
+    }
+
+    fn part_2(grid: &Self::Parsed) -> Self::Answer2 {
+        part_2(grid)
+    }
 }
 
-fn part_1(g: &Grid) -> i64 {
+fn part_1(g: &Grid<Square>) -> i64 {
     count_trees(g, (3, 1))
 }
 
-fn part_2(g: &Grid) -> i64 {
+fn part_2(g: &Grid<Square>) -> i64 {
     [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)]
         .iter()
         .map(|&slope| count_trees(g, slope))
         .product()
 }
 
-fn parse_input(input: &str) -> Result<Vec<Vec<Square>>, Error> {
-    input
-        .lines()
-        .map(|l| {
-            l.chars()
-                .map(|c| match c {
-                    '.' => Ok(Square::Open),
-                    '#' => Ok(Square::Tree),
-                    _ => Err(Error::Parse),
-                })
-                .collect()
-        })
-        .collect()
+fn parse_input(input: &str) -> Result<Grid<Square>, Located<grid::ParseError>> {
+    input.parse()
 }
 
-type Grid = Vec<Vec<Square>>;
-
-#[derive(PartialEq, PartialOrd, Eq, Ord, Debug)]
-enum Error {
-    Parse,
-}
-
-#[derive(PartialEq, PartialOrd, Eq, Ord, Debug)]
-enum Square {
-    Open,
-    Tree,
-}
-
-impl fmt::Display for Square {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Square::Open => ".",
-                Square::Tree => "#",
-            }
-        )
+char_enum! {
+    Square {
+        (Open, '.'),
+        (Tree, '#'),
     }
 }
 
 // TODO: Comment this wrapping around logic
-fn count_trees(g: &Grid, slope: (usize, usize)) -> i64 {
-    let width = g[0].len();
-    let height = g.len();
+fn count_trees(g: &Grid<Square>, slope: (usize, usize)) -> i64 {
+    let width = g.width();
+    let height = g.height();
 
     let mut x = 0;
     let mut y = 0;
     let mut trees = 0;
 
     while y < height {
-        if g[y][x] == Square::Tree {
+        if g[(x, y)] == Square::Tree {
             trees += 1;
         }
         x = (x + slope.0) % width;
@@ -95,24 +82,13 @@ mod tests {
 #.##...#...
 #...##....#
 .#..#...#.#";
-    fn grid_to_string(g: &Grid) -> String {
-        g.iter()
-            .map(|r| {
-                r.iter()
-                    .map(|s| s.to_string())
-                    .collect::<Vec<String>>()
-                    .join("")
-            })
-            .collect::<Vec<String>>()
-            .join("\n")
-    }
 
     #[test]
     fn parse_input_example() {
         // String -> Grid -> String round trip test as I really don't feel like typing
         // out [[Open, Open, Tree...], ...]
         let grid = parse_input(EXAMPLE_INPUT).expect("unable to parse input");
-        assert_eq!(grid_to_string(&grid), EXAMPLE_INPUT);
+        assert_eq!(grid.to_string(), EXAMPLE_INPUT);
     }
 
     #[test]
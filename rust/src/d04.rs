@@ -1,44 +1,61 @@
+use crate::parse::{Context, Located, ResultExt};
+use crate::solution::Solution;
+use crate::str::split_once;
 use std::collections::HashMap;
 use std::str::FromStr;
 use PassportField::*;
 
 const REQUIRED_FIELDS: [PassportField; 7] = [Ecl, Pid, Eyr, Hcl, Byr, Iyr, Hgt];
 
-pub fn run(input: &str) {
-    let passports = parse_input(input).expect("unable to parse input");
-    println!("Part 1: {}", part_1(&passports));
-    println!("Part 2: {}", part_2(&passports));
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Parsed = Vec<HashMap<PassportField, &'a str>>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+    type ParseError = Located<ParseError>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1(passports: &Self::Parsed) -> Self::Answer1 {
+        part_1(passports)
+    }
+
+    fn part_2(passports: &Self::Parsed) -> Self::Answer2 {
+        part_2(passports)
+    }
 }
 
-fn part_1<'a>(passports: &Vec<HashMap<PassportField, &'a str>>) -> usize {
+fn part_1(passports: &[HashMap<PassportField, &str>]) -> usize {
     passports
         .iter()
         .filter(|p| REQUIRED_FIELDS.iter().all(|f| p.contains_key(f)))
         .count()
 }
 
-fn part_2<'a>(passports: &Vec<HashMap<PassportField, &'a str>>) -> usize {
+fn part_2(passports: &[HashMap<PassportField, &str>]) -> usize {
     passports
         .iter()
         .filter(|p| {
             REQUIRED_FIELDS
                 .iter()
-                .all(|f| p.get(f).and_then(|v| Some(f.is_valid(v))).unwrap_or(false))
+                .all(|f| p.get(f).map(|v| f.is_valid(v)).unwrap_or(false))
         })
         .count()
 }
 
-fn parse_input<'a>(input: &'a str) -> Result<Vec<HashMap<PassportField, &'a str>>, ParseError> {
+fn parse_input(input: &str) -> Result<Vec<HashMap<PassportField, &str>>, Located<ParseError>> {
+    let ctx = Context::new(input);
     input
         .split("\n\n") // passports
         .map(|p| {
             p.lines()
                 .flat_map(|l| l.split(" ")) // key:value pairs
                 .map(|kv| {
-                    let mut kv = kv.split(":");
-                    let k = kv.next().ok_or(ParseError::InvalidKV)?;
-                    let v = kv.next().ok_or(ParseError::InvalidKV)?;
-                    Ok((PassportField::from_str(k)?, v))
+                    let (k, v) = split_once(kv, ":").ok_or_else(|| ctx.error(kv, ParseError::InvalidKV))?;
+                    Ok((PassportField::from_str(k).at(&ctx, k)?, v))
                 })
                 .collect()
         })
@@ -46,13 +63,15 @@ fn parse_input<'a>(input: &'a str) -> Result<Vec<HashMap<PassportField, &'a str>
 }
 
 #[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
-enum ParseError {
+pub enum ParseError {
     InvalidKV,
     InvalidKey,
 }
 
+impl_error!(parse ParseError);
+
 #[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
-enum PassportField {
+pub enum PassportField {
     Byr,
     Iyr,
     Eyr,
@@ -97,10 +116,7 @@ impl PassportField {
                 }
             }
             Hcl => &v[..1] == "#" && v[1..].chars().all(|c| c.is_ascii_hexdigit()),
-            Ecl => match v {
-                "amb" | "blu" | "brn" | "gry" | "grn" | "hzl" | "oth" => true,
-                _ => false,
-            },
+            Ecl => matches!(v, "amb" | "blu" | "brn" | "gry" | "grn" | "hzl" | "oth"),
             Pid => v.len() == 9 && v.parse::<i32>().is_ok(),
             Cid => true, // Optional - no restrictions specified
         }
@@ -136,7 +152,7 @@ iyr:2011 ecl:brn hgt:59in
 
     #[test]
     fn parse_input_example() {
-        let parsed = parse_input(&EXAMPLE_INPUT).unwrap();
+        let parsed = parse_input(EXAMPLE_INPUT).unwrap();
         assert_eq!(
             parsed,
             vec![
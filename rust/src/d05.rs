@@ -1,3 +1,5 @@
+use crate::parse::{Context, Located, ResultExt};
+use crate::solution::Solution;
 use std::collections::HashSet;
 
 const ROW_MAX: u8 = 127;
@@ -10,12 +12,30 @@ const fn seat_id(row: u8, col: u8) -> u16 {
     row as u16 * 8 + col as u16
 }
 
-pub fn run(input: &str) {
-    let parsed = parse_input(input).expect("unable to parse input");
-    assert!(parsed.len() > 0, "no boarding passes in input");
-    assert_eq!(input.lines().count(), parsed.len());
-    println!("Part 1: {}", part_1(&parsed).unwrap());
-    println!("Part 2: {}", part_2(&parsed).unwrap());
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Parsed = Vec<SeatId>;
+    type Answer1 = Result<u16, Error>;
+    type Answer2 = Result<u16, Error>;
+    type ParseError = Located<ParseError>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        let parsed = parse_input(input)?;
+        if parsed.is_empty() {
+            return Err(Located::new(ParseError::EmptyInput));
+        }
+        assert_eq!(input.lines().count(), parsed.len());
+        Ok(parsed)
+    }
+
+    fn part_1(seats: &Self::Parsed) -> Self::Answer1 {
+        part_1(seats)
+    }
+
+    fn part_2(seats: &Self::Parsed) -> Self::Answer2 {
+        part_2(seats)
+    }
 }
 
 /// Returns `Ok(max_id)` if `seats` is not empty else `Err`.
@@ -28,44 +48,48 @@ fn part_2(seats: &[SeatId]) -> Result<u16, Error> {
     // as the input isn't that large but I feel like I'm missing something that would
     // allow for another method other than exhaustive search.
     let occupied_seats: HashSet<&u16> = seats.iter().collect();
-    let all_ids = (0..ROW_MAX).flat_map(|r| (0..COL_MAX).map(move |c| seat_id(r, c)));
+    let mut all_ids = (0..ROW_MAX).flat_map(|r| (0..COL_MAX).map(move |c| seat_id(r, c)));
     all_ids
-        .filter(|id| {
+        .find(|id| {
             !occupied_seats.contains(&id)
                 && (occupied_seats.contains(&(id - 1)) && occupied_seats.contains(&(id + 1)))
         })
-        .next()
         .ok_or(Error::NotFound)
 }
 
-fn parse_input(input: &str) -> Result<Vec<SeatId>, ParseError> {
+fn parse_input(input: &str) -> Result<Vec<SeatId>, Located<ParseError>> {
+    let ctx = Context::new(input);
     input
         .lines()
         .map(|l| {
-            if l.len() != 10 {
-                return Err(ParseError::InvalidLength);
+            if l.len() != 10 || !l.is_ascii() {
+                return Err(ctx.error(l, ParseError::InvalidLength));
             }
-            assert_eq!(l.len(), 10);
             let (rowpart, colpart) = l.split_at(7);
-            let row = bsp_search(ROWSPEC, rowpart)?;
-            let col = bsp_search(COLSPEC, colpart)?;
+            let row = bsp_search(ROWSPEC, rowpart).at(&ctx, rowpart)?;
+            let col = bsp_search(COLSPEC, colpart).at(&ctx, colpart)?;
             Ok(seat_id(row, col))
         })
         .collect()
 }
 
 #[derive(PartialEq, PartialOrd, Eq, Ord, Debug)]
-enum ParseError {
+pub enum ParseError {
+    EmptyInput,
     InvalidLength,
     InvalidChar,
 }
 
+impl_error!(parse ParseError);
+
 #[derive(PartialEq, PartialOrd, Eq, Ord, Debug)]
-enum Error {
+pub enum Error {
     NotFound,
 }
 
-type SeatId = u16; // max ID = 127 * 8 + 7 = 1023 < u16::MAX
+impl_error!(solve Error);
+
+pub type SeatId = u16; // max ID = 127 * 8 + 7 = 1023 < u16::MAX
 
 /// Specification for binary space partioning sections e.g. rows, columns.
 type BspSpec = (char, char, u8);
@@ -1,6 +1,8 @@
+use crate::parse::{Context, Located};
+use crate::solution::Solution;
 use std::collections::HashSet;
 
-fn part_1(group_answers: &Vec<Vec<&str>>) -> usize {
+fn part_1(group_answers: &[Vec<&str>]) -> usize {
     // TODO: Avoid creating so many HashSets?
     group_answers
         .iter()
@@ -14,7 +16,7 @@ fn part_1(group_answers: &Vec<Vec<&str>>) -> usize {
         .sum()
 }
 
-fn part_2(group_answers: &Vec<Vec<&str>>) -> usize {
+fn part_2(group_answers: &[Vec<&str>]) -> usize {
     // TODO: Avoid creating so many HashSets?
     group_answers
         .iter()
@@ -38,7 +40,8 @@ fn part_2(group_answers: &Vec<Vec<&str>>) -> usize {
         .sum()
 }
 
-fn parse_input(input: &str) -> Result<Vec<Vec<&str>>, ParseError> {
+fn parse_input(input: &str) -> Result<Vec<Vec<&str>>, Located<ParseError>> {
+    let ctx = Context::new(input);
     input
         .split("\n\n")
         .map(|group| {
@@ -48,7 +51,7 @@ fn parse_input(input: &str) -> Result<Vec<Vec<&str>>, ParseError> {
                     if l.chars().all(|c| c.is_ascii_lowercase()) {
                         Ok(l)
                     } else {
-                        Err(ParseError::InvalidChar)
+                        Err(ctx.error(l, ParseError::InvalidChar))
                     }
                 })
                 .collect()
@@ -56,17 +59,34 @@ fn parse_input(input: &str) -> Result<Vec<Vec<&str>>, ParseError> {
         .collect()
 }
 
-pub fn run(input: &str) {
-    let group_answers = parse_input(input).expect("unable to parse input");
-    println!("Part 1: {}", part_1(&group_answers));
-    println!("Part 2: {}", part_2(&group_answers));
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Parsed = Vec<Vec<&'a str>>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+    type ParseError = Located<ParseError>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1(group_answers: &Self::Parsed) -> Self::Answer1 {
+        part_1(group_answers)
+    }
+
+    fn part_2(group_answers: &Self::Parsed) -> Self::Answer2 {
+        part_2(group_answers)
+    }
 }
 
 #[derive(PartialEq, PartialOrd, Eq, Ord, Debug)]
-enum ParseError {
+pub enum ParseError {
     InvalidChar,
 }
 
+impl_error!(parse ParseError);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,7 +135,10 @@ a
 
 b
 ";
-        assert_eq!(parse_input(&input), Err(ParseError::InvalidChar));
+        assert_eq!(
+            parse_input(input).map_err(|e| e.error),
+            Err(ParseError::InvalidChar)
+        );
     }
 
     #[test]
@@ -1,3 +1,5 @@
+use crate::parse::{Context, Located, ResultExt};
+use crate::solution::Solution;
 use crate::str::{rsplit_once, split_once};
 use std::collections::{HashMap, VecDeque};
 
@@ -13,12 +15,12 @@ const MY_BAG: &str = "shiny gold";
 ///   "dotted black" -> [],
 /// }
 /// ```
-type BagRules<'a> = HashMap<&'a str, Vec<(&'a str, usize)>>;
+pub type BagRules<'a> = HashMap<&'a str, Vec<(&'a str, usize)>>;
 
 fn part_1(rules: &BagRules) -> usize {
     let possible_outer_bags = rules
         .keys()
-        .filter(|&&k| k != MY_BAG && rules.get(k).unwrap().len() > 0);
+        .filter(|&&k| k != MY_BAG && !rules.get(k).unwrap().is_empty());
     let mut found_outer_bags: Vec<&str> = Vec::new();
     for start_bag in possible_outer_bags {
         let mut queue: VecDeque<&str> = VecDeque::new();
@@ -60,8 +62,7 @@ fn count_required_bags_iterative<'a>(rules: &'a BagRules, bag: &'a str) -> usize
     let mut total = 0;
     let mut s = vec![(bag, 1)];
 
-    while !s.is_empty() {
-        let (bag, n) = s.pop().unwrap();
+    while let Some((bag, n)) = s.pop() {
         total += n;
         for (child, x) in rules.get(bag).unwrap() {
             s.push((child, n * x))
@@ -71,10 +72,25 @@ fn count_required_bags_iterative<'a>(rules: &'a BagRules, bag: &'a str) -> usize
     total - 1 // Don't count start bag
 }
 
-pub fn run(input: &str) {
-    let rules = parse_input(input).expect("unable to parse input");
-    println!("Part 1: {}", part_1(&rules));
-    println!("Part 2: {}", part_2(&rules));
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Parsed = BagRules<'a>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+    type ParseError = Located<ParseError>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1(rules: &Self::Parsed) -> Self::Answer1 {
+        part_1(rules)
+    }
+
+    fn part_2(rules: &Self::Parsed) -> Self::Answer2 {
+        part_2(rules)
+    }
 }
 
 /// Rough syntax for puzzle input:
@@ -85,29 +101,61 @@ pub fn run(input: &str) {
 /// rule-item = { digit } " " bag "bag" [ "s" ]
 /// bag = { letter } " " { letter }
 /// ```
-fn parse_input(input: &str) -> Result<BagRules, ParseError> {
-    // FIXME: Actually return error cases
-    Ok(input
+///
+/// Every bag contained in another, and `MY_BAG`, must have a rule of its own.
+fn parse_input(input: &str) -> Result<BagRules<'_>, Located<ParseError>> {
+    let ctx = Context::new(input);
+    let rules: BagRules = input
         .lines()
         .map(|line| {
-            let (l, r) = split_once(line, " contain ").unwrap();
-            let (_, parent) = rsplit_once(l, " ").unwrap();
+            let invalid_rule = || ctx.error(line, ParseError::InvalidRule);
+            let (l, r) = split_once(line, " contain ").ok_or_else(invalid_rule)?;
+            let (_, parent) = rsplit_once(l, " ").ok_or_else(invalid_rule)?;
             if r == "no other bags." {
-                (parent, vec![])
+                Ok((parent, vec![]))
             } else {
-                let children = r.trim_end_matches(".").split(", ").map(|child| {
-                    let (quantity, rest) = split_once(child, " ").unwrap();
-                    let (_, bag) = rsplit_once(rest, " ").unwrap();
-                    (bag, quantity.parse::<usize>().unwrap())
-                });
-                (parent, children.collect())
+                let children = r
+                    .trim_end_matches(".")
+                    .split(", ")
+                    .map(|child| {
+                        let invalid_item = || ctx.error(child, ParseError::InvalidRuleItem);
+                        let (quantity, rest) = split_once(child, " ").ok_or_else(invalid_item)?;
+                        let (_, bag) = rsplit_once(rest, " ").ok_or_else(invalid_item)?;
+                        let quantity = quantity
+                            .parse::<usize>()
+                            .map_err(ParseError::InvalidQuantity)
+                            .at(&ctx, quantity)?;
+                        Ok((bag, quantity))
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok((parent, children))
             }
         })
-        .collect())
+        .collect::<Result<_, _>>()?;
+
+    if !rules.contains_key(MY_BAG) {
+        return Err(ctx.error(ctx.end(), ParseError::MissingRule(MY_BAG.to_string())));
+    }
+    if let Some(&(bag, _)) = rules
+        .values()
+        .flatten()
+        .find(|(bag, _)| !rules.contains_key(bag))
+    {
+        return Err(ctx.error(bag, ParseError::MissingRule(bag.to_string())));
+    }
+    Ok(rules)
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum ParseError {
+    InvalidRule,
+    InvalidRuleItem,
+    InvalidQuantity(std::num::ParseIntError),
+    /// A bag had no rule for its contents
+    MissingRule(String),
 }
 
-#[derive(PartialEq, PartialOrd, Eq, Ord, Debug)]
-enum ParseError {}
+impl_error!(parse ParseError);
 
 #[cfg(test)]
 mod tests {
@@ -166,4 +214,19 @@ dark violet bags contain no other bags.";
         let rules = parse_input(input).unwrap();
         assert_eq!(part_2(&rules), 126);
     }
+
+    #[test]
+    fn parse_input_missing_rules() {
+        let error = |input| parse_input(input).unwrap_err().error;
+        assert_eq!(error(""), ParseError::MissingRule(MY_BAG.to_string()));
+        assert_eq!(
+            error("dark red bags contain no other bags."),
+            ParseError::MissingRule(MY_BAG.to_string())
+        );
+
+        let e = parse_input("shiny gold bags contain 1 dark red bag, 2 faded blue bags.\nfaded blue bags contain no other bags.").unwrap_err();
+        assert_eq!(e.error, ParseError::MissingRule("dark red".to_string()));
+        let location = e.location.unwrap();
+        assert_eq!((location.line, location.col, location.len), (1, 27, 8));
+    }
 }
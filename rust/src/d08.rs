@@ -1,15 +1,17 @@
+use crate::parse::{Context, Located, ResultExt};
+use crate::solution::Solution;
 use crate::str::split_once;
 use std::collections::HashSet;
 use Opcode::*;
 
-fn part_1(prg: &Vec<Instruction>) -> Result<i32, Error> {
+fn part_1(prg: &[Instruction]) -> Result<i32, Error> {
     match execute_program(prg) {
         Err(x) => Ok(x),
         _ => Err(Error::ExpectedInfiniteLoop),
     }
 }
 
-fn part_2(prg: &Vec<Instruction>) -> Result<i32, Error> {
+fn part_2(prg: &[Instruction]) -> Result<i32, Error> {
     // Brute force search for the opcode to fix
     // TODO: I feel like there might be a trick I'm missing in the puzzle that means
     // a brute force search isn't required...
@@ -35,7 +37,7 @@ fn part_2(prg: &Vec<Instruction>) -> Result<i32, Error> {
 /// If the program terminates then `Result::Ok` is returned, containing the final accumulator
 /// value. If an inifinite loop is encountered then `Result::Err` is returned, containing the
 /// accumulator value immediately before the loop is entered.
-fn execute_program(prg: &Vec<Instruction>) -> Result<i32, i32> {
+fn execute_program(prg: &[Instruction]) -> Result<i32, i32> {
     let mut ip = 0;
     let mut acc = 0;
     let mut executed = HashSet::new();
@@ -50,7 +52,7 @@ fn execute_program(prg: &Vec<Instruction>) -> Result<i32, i32> {
         match instruction {
             (Acc, x) => acc += x,
             (Jmp, x) if *x < 0 => {
-                ip = ip.checked_sub(x.abs() as usize).unwrap();
+                ip = ip.checked_sub(x.unsigned_abs() as usize).unwrap();
                 continue;
             }
             (Jmp, x) => {
@@ -65,59 +67,75 @@ fn execute_program(prg: &Vec<Instruction>) -> Result<i32, i32> {
     Ok(acc)
 }
 
-fn parse_input(input: &str) -> Result<Vec<Instruction>, ParseError> {
+fn parse_input(input: &str) -> Result<Vec<Instruction>, Located<ParseError>> {
     // TODO: Clean this up a bit
+    let ctx = Context::new(input);
     input
         .lines()
         .map(|l| {
-            let (op, arg) = split_once(l, " ").ok_or(ParseError::InvalidInstruction)?;
+            let (op, arg) =
+                split_once(l, " ").ok_or_else(|| ctx.error(l, ParseError::InvalidInstruction))?;
             let x = arg
                 .parse::<i32>()
-                .map_err(|e| ParseError::InvalidInteger(e))?;
+                .map_err(ParseError::InvalidInteger)
+                .at(&ctx, arg)?;
             match op {
                 "acc" => Ok((Acc, x)),
                 "jmp" => Ok((Jmp, x)),
                 "nop" => Ok((Nop, x)),
-                _ => Err(ParseError::InvalidOpcode),
+                _ => Err(ctx.error(op, ParseError::InvalidOpcode)),
             }
         })
         .collect()
 }
 
-pub fn run(input: &str) {
-    let program = parse_input(input).expect("unable to parse input");
-    println!(
-        "Part 1: {}",
-        part_1(&program).expect("no infinite loop found")
-    );
-    println!(
-        "Part 2: {}",
-        part_2(&program).expect("unable to find a fix for input program")
-    );
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Parsed = Vec<Instruction>;
+    type Answer1 = Result<i32, Error>;
+    type Answer2 = Result<i32, Error>;
+    type ParseError = Located<ParseError>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1(program: &Self::Parsed) -> Self::Answer1 {
+        part_1(program)
+    }
+
+    fn part_2(program: &Self::Parsed) -> Self::Answer2 {
+        part_2(program)
+    }
 }
 
-type Instruction = (Opcode, i32);
+pub type Instruction = (Opcode, i32);
 
 #[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Hash, Clone)]
-enum Opcode {
+pub enum Opcode {
     Acc,
     Jmp,
     Nop,
 }
 
 #[derive(PartialEq, Eq, Debug)]
-enum ParseError {
+pub enum ParseError {
     InvalidOpcode,
     InvalidInstruction,
     InvalidInteger(std::num::ParseIntError),
 }
 
+impl_error!(parse ParseError);
+
 #[derive(PartialEq, Eq, Debug)]
-enum Error {
+pub enum Error {
     ExpectedInfiniteLoop,
     ProgramFixNotFound,
 }
 
+impl_error!(solve Error);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,3 +1,5 @@
+use crate::parse::{Context, Located, ResultExt};
+use crate::solution::Solution;
 use itertools::Itertools;
 
 const PREAMBLE_LEN: usize = 25;
@@ -7,7 +9,7 @@ const PREAMBLE_LEN: usize = 25;
 ///
 /// - Values must be different
 /// - There may exist multiple such pairs
-fn part_1(data: &Vec<usize>, preamble_len: usize) -> Result<usize, Error> {
+fn part_1(data: &[usize], preamble_len: usize) -> Result<usize, Error> {
     // TODO: Use a more sophisticated algorithm (D.P?)
     // Brute force approach: Starting from i = preamble_len, iterate through length 2 combinations
     // of data[i - preamble_len: preamble_len]. If any pair has the property, increment i and move
@@ -17,7 +19,7 @@ fn part_1(data: &Vec<usize>, preamble_len: usize) -> Result<usize, Error> {
             .iter()
             .combinations(2)
             .find(|c| c.iter().copied().sum::<usize>() == data[i])
-            == None
+            .is_none()
         {
             return Ok(data[i]);
         }
@@ -26,7 +28,7 @@ fn part_1(data: &Vec<usize>, preamble_len: usize) -> Result<usize, Error> {
     Err(Error::NotFound)
 }
 
-fn part_2(data: &Vec<usize>, target: usize) -> Result<usize, Error> {
+fn part_2(data: &[usize], target: usize) -> Result<usize, Error> {
     // TODO: Use a more sophisticated algorithm (D.P?)
     // Brute force approach: Starting from i = 0, j = i+2, iterate through contiguous sequences
     // vec[i..j]. If vec[i..j].sum() < target, increment j. If vec[i..j].sum() == target, return
@@ -47,31 +49,51 @@ fn part_2(data: &Vec<usize>, target: usize) -> Result<usize, Error> {
 }
 
 /// Assuming XMAS encryption doesn't support negative numbers.
-fn parse_input(input: &str) -> Result<Vec<usize>, Error> {
+fn parse_input(input: &str) -> Result<Vec<usize>, Located<Error>> {
+    let ctx = Context::new(input);
     input
         .lines()
-        .map(|l| l.trim().parse().map_err(|e| Error::Parse(e)))
+        .map(|l| l.trim().parse().map_err(Error::Parse).at(&ctx, l))
         .collect()
 }
 
-pub fn run(input: &str) {
-    let encoded_data = parse_input(input).expect("unable to parse input");
+pub struct Solver;
 
-    let incorrect_value = part_1(&encoded_data, PREAMBLE_LEN)
-        .expect("unable to find number in input which does not have the XMAS encoding property");
-    println!("Part 1: {}", incorrect_value);
-    println!(
-        "Part 2: {}",
-        part_2(&encoded_data, incorrect_value).expect("unable to find the encryption weakness")
-    );
+impl<'a> Solution<'a> for Solver {
+    type Parsed = Vec<usize>;
+    type Answer1 = Result<usize, Error>;
+    type Answer2 = Result<usize, Error>;
+    type ParseError = Located<Error>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1(data: &Self::Parsed) -> Self::Answer1 {
+        part_1(data, PREAMBLE_LEN)
+    }
+
+    fn part_2(data: &Self::Parsed) -> Self::Answer2 {
+        let incorrect_value = part_1(data, PREAMBLE_LEN)?;
+        part_2(data, incorrect_value)
+    }
 }
 
 #[derive(PartialEq, Eq, Debug)]
-enum Error {
+pub enum Error {
     NotFound,
     Parse(std::num::ParseIntError),
 }
 
+impl From<Error> for crate::error::Error {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::Parse(_) => Self::parse(e),
+            _ => Self::solve(e),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
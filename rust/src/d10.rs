@@ -1,3 +1,5 @@
+use crate::parse::{Context, Located, ResultExt};
+use crate::solution::Solution;
 use std::cmp::min;
 
 /// The puzzle description is, I suspect, deliberately convoluted. Since *every* adapter must be
@@ -5,7 +7,7 @@ use std::cmp::min;
 /// a single valid configuration - sorted in ascending order.
 ///
 /// Assumes `ratings` is *already* sorted.
-fn part_1(ratings: &Vec<usize>) -> Result<usize, Error> {
+fn part_1(ratings: &[usize]) -> Result<usize, Error> {
     let mut diff_1 = 0;
     let mut diff_3 = 0;
 
@@ -33,7 +35,7 @@ fn part_1(ratings: &Vec<usize>) -> Result<usize, Error> {
 ///   valid arrangements of all the adapters.
 ///
 /// Assumes `ratings` is *already* sorted.
-fn part_2(ratings: &Vec<usize>) -> Result<usize, Error> {
+fn part_2(ratings: &[usize]) -> Result<usize, Error> {
     let n = ratings.len();
     // Holds cumulative number of arrangements for each adapter - counts[i] is the number
     // of valid arrangements for adapters in ratings[i..n]. At the end of the loop
@@ -54,32 +56,54 @@ fn part_2(ratings: &Vec<usize>) -> Result<usize, Error> {
     Ok(counts[0])
 }
 
-fn parse_input(input: &str) -> Result<Vec<usize>, Error> {
+fn parse_input(input: &str) -> Result<Vec<usize>, Located<Error>> {
+    let ctx = Context::new(input);
     let mut ratings: Vec<usize> = input
         .lines()
-        .map(|l| l.trim().parse().map_err(|e| Error::Parse(e)))
-        .collect::<Result<Vec<usize>, Error>>()?;
+        .map(|l| l.trim().parse().map_err(Error::Parse).at(&ctx, l))
+        .collect::<Result<Vec<usize>, _>>()?;
     ratings.push(0);
     ratings.sort();
     ratings.push(ratings.last().expect("empty input") + 3);
     Ok(ratings)
 }
 
-pub fn run(input: &str) {
-    let ratings = parse_input(input).expect("unable to parse input");
-    println!(
-        "Part 1: {}",
-        part_1(&ratings).expect("invalid adapter difference encountered")
-    );
-    println!("Part 2: {}", part_2(&ratings).unwrap()); // FIXME
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Parsed = Vec<usize>;
+    type Answer1 = Result<usize, Error>;
+    type Answer2 = Result<usize, Error>;
+    type ParseError = Located<Error>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1(ratings: &Self::Parsed) -> Self::Answer1 {
+        part_1(ratings)
+    }
+
+    fn part_2(ratings: &Self::Parsed) -> Self::Answer2 {
+        part_2(ratings)
+    }
 }
 
 #[derive(PartialEq, Eq, Debug)]
-enum Error {
+pub enum Error {
     Parse(std::num::ParseIntError),
     InvalidAdapterDifference(usize),
 }
 
+impl From<Error> for crate::error::Error {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::Parse(_) => Self::parse(e),
+            _ => Self::solve(e),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
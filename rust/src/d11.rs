@@ -1,22 +1,18 @@
+use crate::grid::{self, Cell, Grid};
+use crate::parse::Located;
+use crate::solution::{Part, Solution};
+use crate::visualise::{Frame, Recording};
 use std::fmt;
 use Location::*;
 use SeatState::*;
 
-// TODO: Plenty of room for cleanup/de-duplication between part_1 and part_2 (mainly in various
-// methods on Grid) but this works and executes v fast on my laptop.
-
 /// Key points:
 /// - Rules based on adjacent seats:
 ///     - Empty w/ `0` adjacent occupied -> occupied
 ///     - Occupied w/ `>=4` visible occupied seats -> empty
 ///     - Floor never changes
-fn part_1(layout: &mut Grid) -> usize {
-    // TODO: Limit this to prevent infinite loop in the case of malformed input?
-    loop {
-        if !layout.tick() {
-            return layout.count_occupied();
-        }
-    }
+fn part_1(layout: &mut Layout) -> Result<usize, Error> {
+    layout.simulate(&SeatRules::PART_1)
 }
 
 /// Key points:
@@ -39,246 +35,357 @@ fn part_1(layout: &mut Grid) -> usize {
 /// #/..#..\.
 /// /..#....\
 /// ```
-/// "Ray casting" approach:
-/// - Generate coordinates for each location in a "ray" by following the points along it's vector
-///     - Repeatedly add the unit vector of the ray to the current coordinate
-/// - Traverse the ray coordinates until a visible seat is found or the boundary of the grid is reached
-fn part_2(layout: &mut Grid) -> usize {
-    loop {
-        if !layout.tick_v2() {
-            return layout.count_occupied();
-        }
-    }
+/// Finding visible seats:
+/// - The visible seats in a direction are the next seat either way along the line through a seat
+/// - Sweep along every horizontal, vertical and diagonal line of the grid once, linking each seat
+///   to the previous seat seen on that line (if it is close enough)
+/// - See `Simulation` for how the neighbours found this way are used
+fn part_2(layout: &mut Layout) -> Result<usize, Error> {
+    layout.simulate(&SeatRules::PART_2)
 }
 
-fn parse_input(input: &str) -> Result<Grid, ParseError> {
-    // TODO: Clean this up
-    let width = input.lines().next().ok_or(ParseError::EmptyInput)?.len();
-    let g = input
-        .lines()
-        .flat_map(|l| {
-            if l.len() != width {
-                Err(ParseError::MalformedGrid)
-            } else {
-                Ok(l.chars()
-                    .map(|c| match c {
-                        'L' => Ok(Seat(Empty)),
-                        '#' => Ok(Seat(Occupied)),
-                        '.' => Ok(Floor),
-                        _ => Err(ParseError::InvalidCharacter(c)),
-                    })
-                    .collect::<Result<Vec<Location>, ParseError>>()?)
-            }
-        })
-        .flatten()
-        .collect::<Vec<Location>>();
-    // Each row has already been checked for equal width -> height calculation is correct
-    let height = g.len() / width;
-    Ok(Grid { g, width, height })
+fn parse_input(input: &str) -> Result<Layout, Located<grid::ParseError>> {
+    input.parse().map(Layout)
 }
 
-pub fn run(input: &str) {
-    let layout = parse_input(input).expect("unable to parse input");
-    println!("Part 1: {}", part_1(&mut layout.clone()));
-    println!("Part 2: {}", part_2(&mut layout.clone()));
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Parsed = Layout;
+    type Answer1 = Result<usize, Error>;
+    type Answer2 = Result<usize, Error>;
+    type ParseError = Located<grid::ParseError>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1(layout: &Self::Parsed) -> Self::Answer1 {
+        part_1(&mut layout.clone())
+    }
+
+    fn part_2(layout: &Self::Parsed) -> Self::Answer2 {
+        part_2(&mut layout.clone())
+    }
+
+    /// Every round of seating until equilibrium (or `max_rounds`).
+    fn visualise(layout: &Self::Parsed, part: Part, recording: &mut Recording) -> bool {
+        let rules = match part {
+            Part::One => SeatRules::PART_1,
+            Part::Two => SeatRules::PART_2,
+        };
+        // The rounds leading up to a failure to reach equilibrium are still worth seeing
+        let _ = layout.clone().simulate_recorded(&rules, recording);
+        true
+    }
 }
 
 #[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone, Copy)]
-enum SeatState {
+pub enum SeatState {
     Empty,
     Occupied,
 }
 
 #[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone, Copy)]
-enum Location {
+pub enum Location {
     Floor,
     Seat(SeatState),
 }
 
-#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone)]
-struct Grid {
-    /// Flat representation of grid shape `(width, height)`
-    g: Vec<Location>,
-    width: usize,
-    height: usize,
+impl Cell for Location {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            'L' => Some(Seat(Empty)),
+            '#' => Some(Seat(Occupied)),
+            '.' => Some(Floor),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Floor => '.',
+            Seat(Empty) => 'L',
+            Seat(Occupied) => '#',
+        }
+    }
 }
 
-impl Grid {
-    const ADJACENT_DIRS: [(i8, i8); 8] = [
-        (-1, -1),
-        (-1, 0),
-        (-1, 1),
-        (0, -1),
-        (0, 1),
-        (1, -1),
-        (1, 0),
-        (1, 1),
-    ];
-
-    /// Seat transition rules:
-    /// - Empty & `0` adjacent occupied -> occupied
-    /// - Occupied `& >= 4` adjacent occupied -> empty
-    /// - Otherwise -> no change
-    fn tick(&mut self) -> bool {
-        let mut g_next = self.g.clone();
-        let mut change = false;
-
-        self.g
-            .iter()
-            .enumerate()
-            .filter_map(|(i, l)| match l {
-                Floor => None,
-                Seat(state) => Some((i, state)),
-            })
-            .for_each(|(i, s)| match (s, self.adjacent_occupied(i)) {
-                (Empty, 0) => {
-                    change = true;
-                    g_next[i] = Seat(Occupied);
-                }
-                (Occupied, x) if x >= 4 => {
-                    change = true;
-                    g_next[i] = Seat(Empty)
-                }
-                _ => (),
-            });
+/// Which seats are considered neighbours of a seat.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Neighbourhood {
+    /// The (up to) 8 immediately adjacent seats
+    Adjacent,
+    /// The first seat visible in each of the 8 directions, looking past floor
+    Visible,
+}
+
+/// Configuration of the seat transition rules:
+/// - Empty & `0` occupied neighbours -> occupied
+/// - Occupied & `>= crowding` occupied neighbours -> empty
+/// - Otherwise -> no change
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct SeatRules {
+    pub neighbourhood: Neighbourhood,
+    pub crowding: usize,
+    /// Maximum distance at which a seat is `Visible`, or `None` for the edge of the layout
+    pub max_distance: Option<usize>,
+    /// Number of rounds after which the layout is assumed not to stabilise
+    pub max_rounds: usize,
+}
+
+impl SeatRules {
+    pub const PART_1: SeatRules = SeatRules {
+        neighbourhood: Neighbourhood::Adjacent,
+        crowding: 4,
+        max_distance: None,
+        max_rounds: 10_000,
+    };
 
-        self.g = g_next;
-        change
+    pub const PART_2: SeatRules = SeatRules {
+        neighbourhood: Neighbourhood::Visible,
+        crowding: 5,
+        max_distance: None,
+        max_rounds: 10_000,
+    };
+
+    /// Maximum distance of a neighbour in any direction.
+    fn range(&self) -> usize {
+        match self.neighbourhood {
+            Neighbourhood::Adjacent => 1,
+            Neighbourhood::Visible => self.max_distance.unwrap_or(usize::MAX),
+        }
     }
+}
 
-    /// Seat transition rules:
-    /// - Empty & `0` visible occupied -> occupied
-    /// - Occupied `& >= 5` visible occupied -> empty
-    /// - Otherwise -> no change
-    fn tick_v2(&mut self) -> bool {
-        let mut g_next = self.g.clone();
-        let mut change = false;
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Layout(Grid<Location>);
 
-        self.g
-            .iter()
-            .enumerate()
-            .filter_map(|(i, l)| match l {
-                Floor => None,
-                Seat(state) => Some((i, state)),
-            })
-            .for_each(|(i, s)| match (s, self.visible_occupied(i)) {
-                (Empty, 0) => {
-                    change = true;
-                    g_next[i] = Seat(Occupied);
-                }
-                (Occupied, x) if x >= 5 => {
-                    change = true;
-                    g_next[i] = Seat(Empty)
-                }
-                _ => (),
-            });
+impl Layout {
+    /// Apply `rules` until no seats change, returning the number of occupied seats.
+    fn simulate(&mut self, rules: &SeatRules) -> Result<usize, Error> {
+        self.simulate_recorded(rules, &mut Recording::disabled())
+    }
 
-        self.g = g_next;
-        change
+    /// `simulate`, recording the initial layout and the layout after every round.
+    fn simulate_recorded(
+        &mut self,
+        rules: &SeatRules,
+        recording: &mut Recording,
+    ) -> Result<usize, Error> {
+        let mut sim = Simulation::new(&self.0, rules);
+        recording.record(|| self.frame());
+        let mut stable = false;
+        for _ in 0..rules.max_rounds {
+            if !sim.round() {
+                stable = true;
+                break;
+            }
+            if recording.is_enabled() {
+                sim.write(&mut self.0);
+                recording.record(|| self.frame());
+            }
+        }
+        sim.write(&mut self.0);
+        if stable {
+            Ok(self.count_occupied())
+        } else {
+            Err(Error::NoEquilibrium(rules.max_rounds))
+        }
     }
 
-    fn count_occupied(&self) -> usize {
-        self.g.iter().filter(|l| **l == Seat(Occupied)).count()
+    fn frame(&self) -> Frame {
+        self.0.map(Cell::to_char)
     }
 
-    fn adjacent_occupied(&self, idx: usize) -> usize {
-        let (x, y) = self.pos(idx).unwrap(); // FIXME
-        Grid::ADJACENT_DIRS
+    fn count_occupied(&self) -> usize {
+        self.0
+            .cells()
             .iter()
-            .filter_map(|(dx, dy)| {
-                // Using Option::and_then to filter out-of-bounds locations e.g. (-1, -1)
-                let x1 = checked_add(x, *dx);
-                let y1 = checked_add(y, *dy);
-                x1.zip(y1)
-                    .and_then(|(x, y)| self.loc(x, y))
-                    .and_then(|i| self.g.get(i))
-            })
-            .filter(|l| **l == Location::Seat(Occupied))
+            .filter(|l| **l == Seat(Occupied))
             .count()
     }
+}
+
+/// Neighbours of a seat as indices into `Simulation::seats`. Each seat has at most one neighbour
+/// in each of the 8 directions.
+#[derive(Debug, Clone, Copy, Default)]
+struct Neighbours {
+    len: u8,
+    seats: [u32; 8],
+}
 
-    fn visible_occupied(&self, idx: usize) -> usize {
-        let (x, y) = self.pos(idx).unwrap(); // FIXME
-        Grid::ADJACENT_DIRS
+impl Neighbours {
+    fn push(&mut self, seat: u32) {
+        self.seats[self.len as usize] = seat;
+        self.len += 1;
+    }
+
+    fn as_slice(&self) -> &[u32] {
+        &self.seats[..self.len as usize]
+    }
+}
+
+/// State of the seats in a layout, with the neighbours of every seat precomputed for a set of
+/// `SeatRules`.
+///
+/// Floor never changes, so only seats are tracked. Rather than counting the occupied neighbours
+/// of every seat each round, the count for each seat is kept up to date as its neighbours change.
+/// A seat can only change if one of its neighbours (or itself) changed in the previous round, so
+/// only those seats are re-evaluated.
+///
+/// The changes decided in a round are buffered and only applied once every seat has been
+/// evaluated, so that all seats change simultaneously. The seats to evaluate are double buffered
+/// as bitsets: those marked dirty by the changes in one round are evaluated in the next, in order
+/// of their position in the grid for better locality than a work list.
+struct Simulation {
+    crowding: usize,
+    /// Index in the grid of each seat, in ascending order
+    seats: Vec<usize>,
+    neighbours: Vec<Neighbours>,
+    occupied: Vec<bool>,
+    occupied_neighbours: Vec<u8>,
+    /// Bitset of seats to evaluate in the current round
+    dirty: Vec<u64>,
+    /// Bitset of seats to evaluate in the next round
+    next_dirty: Vec<u64>,
+    /// Seats which change in the current round
+    changed: Vec<u32>,
+}
+
+impl Simulation {
+    /// Lines through the grid in each direction. The opposite directions are covered by linking
+    /// seats both ways.
+    const LINES: [(isize, isize); 4] = [(1, 0), (0, 1), (1, 1), (-1, 1)];
+
+    fn new(grid: &Grid<Location>, rules: &SeatRules) -> Self {
+        let (seats, occupied): (Vec<usize>, Vec<bool>) = grid
+            .cells()
             .iter()
-            .filter_map(|(dx, dy)| {
-                // Repeatedly apply dx, dy until seat is found or out of bounds
-                let mut ray = (1..)
-                    .map(|i| {
-                        let x1 = checked_add(x, *dx * i);
-                        let y1 = checked_add(y, *dy * i);
-                        x1.zip(y1)
-                            .and_then(|(x, y)| self.loc(x, y))
-                            .and_then(|i| self.g.get(i))
-                    })
-                    // Edge of grid reached
-                    .take_while(|x| x.is_some());
-                // Follow ray until *first* seat
-                ray.find(|l| match l {
-                    Some(Seat(_)) => true,
-                    _ => false,
-                })
-                .and_then(|l| Some(l.unwrap()))
+            .enumerate()
+            .filter_map(|(i, l)| match l {
+                Floor => None,
+                Seat(state) => Some((i, *state == Occupied)),
             })
-            .filter(|l| **l == Seat(Occupied))
-            .count()
+            .unzip();
+        let mut seat_of = vec![None; grid.cells().len()];
+        for (s, &cell) in seats.iter().enumerate() {
+            seat_of[cell] = Some(s as u32);
+        }
+
+        let range = rules.range();
+        let mut neighbours = vec![Neighbours::default(); seats.len()];
+        for &(dx, dy) in &Self::LINES {
+            // Lines start at cells with no predecessor in the grid
+            let starts = grid
+                .iter()
+                .map(|(pos, _)| pos)
+                .filter(|&pos| grid.step(pos, (-dx, -dy), 1).is_none());
+            for start in starts {
+                let line = std::iter::once(start).chain(grid.ray(start, (dx, dy)).map(|(p, _)| p));
+                let mut previous: Option<(u32, usize)> = None;
+                for (distance, pos) in line.enumerate() {
+                    if let Some(s) = seat_of[grid.index_of(pos).unwrap()] {
+                        match previous {
+                            Some((p, d)) if distance - d <= range => {
+                                neighbours[p as usize].push(s);
+                                neighbours[s as usize].push(p);
+                            }
+                            _ => (),
+                        }
+                        previous = Some((s, distance));
+                    }
+                }
+            }
+        }
+
+        let occupied_neighbours = neighbours
+            .iter()
+            .map(|n| n.as_slice().iter().filter(|&&s| occupied[s as usize]).count() as u8)
+            .collect();
+        Simulation {
+            crowding: rules.crowding,
+            dirty: vec![u64::MAX; seats.len().div_ceil(64)],
+            next_dirty: vec![0; seats.len().div_ceil(64)],
+            changed: Vec::new(),
+            seats,
+            neighbours,
+            occupied,
+            occupied_neighbours,
+        }
     }
 
-    fn pos(&self, idx: usize) -> Option<(usize, usize)> {
-        if idx < self.g.len() {
-            Some((idx % self.width, idx / self.width))
-        } else {
-            None
+    /// Apply the rules to every seat simultaneously, returning whether any seats changed.
+    fn round(&mut self) -> bool {
+        self.changed.clear();
+        for (w, &word) in self.dirty.iter().enumerate() {
+            let mut word = word;
+            while word != 0 {
+                let s = w * 64 + word.trailing_zeros() as usize;
+                word &= word - 1;
+                // The last word may be marked beyond the last seat
+                if s >= self.seats.len() {
+                    break;
+                }
+                let n = self.occupied_neighbours[s] as usize;
+                let flip = if self.occupied[s] {
+                    n >= self.crowding
+                } else {
+                    n == 0
+                };
+                if flip {
+                    self.changed.push(s as u32);
+                }
+            }
         }
+
+        for &s in &self.changed {
+            let s = s as usize;
+            self.occupied[s] = !self.occupied[s];
+            // +1 if the seat became occupied, otherwise -1
+            let delta = if self.occupied[s] { 1 } else { u8::MAX };
+            self.next_dirty[s / 64] |= 1 << (s % 64);
+            for &n in self.neighbours[s].as_slice() {
+                let n = n as usize;
+                self.occupied_neighbours[n] = self.occupied_neighbours[n].wrapping_add(delta);
+                self.next_dirty[n / 64] |= 1 << (n % 64);
+            }
+        }
+        std::mem::swap(&mut self.dirty, &mut self.next_dirty);
+        self.next_dirty.iter_mut().for_each(|w| *w = 0);
+        !self.changed.is_empty()
     }
 
-    fn loc(&self, x: usize, y: usize) -> Option<usize> {
-        if x < self.width && y < self.height {
-            Some(y * self.width + x)
-        } else {
-            None
+    /// Update the seats of `grid` (the grid the simulation was created from) to the current state.
+    fn write(&self, grid: &mut Grid<Location>) {
+        for (&cell, &occupied) in self.seats.iter().zip(&self.occupied) {
+            grid.cells_mut()[cell] = Seat(if occupied { Occupied } else { Empty });
         }
     }
-}
 
-impl fmt::Display for Grid {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            self.g
-                .chunks(self.width)
-                .map(|c| {
-                    c.iter()
-                        .map(|l| match l {
-                            Floor => ".",
-                            Seat(Empty) => "L",
-                            Seat(Occupied) => "#",
-                        })
-                        .collect::<Vec<_>>()
-                        .join("")
-                })
-                .collect::<Vec<_>>()
-                .join("\n")
-        )
+    /// Number of occupied neighbours of the seat at `pos`, or `None` if it is not a seat.
+    #[cfg(test)]
+    fn occupied_neighbours(&self, grid: &Grid<Location>, pos: grid::Pos) -> Option<usize> {
+        let cell = grid.index_of(pos)?;
+        let s = self.seats.binary_search(&cell).ok()?;
+        Some(self.occupied_neighbours[s] as usize)
     }
 }
 
-fn checked_add(x: usize, rhs: i8) -> Option<usize> {
-    if rhs < 0 {
-        x.checked_sub(rhs.abs() as usize)
-    } else {
-        x.checked_add(rhs as usize)
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
     }
 }
 
 #[derive(PartialEq, PartialOrd, Eq, Ord, Debug)]
-enum ParseError {
-    EmptyInput,
-    MalformedGrid,
-    InvalidCharacter(char),
+pub enum Error {
+    /// The layout did not stabilise within the given number of rounds
+    NoEquilibrium(usize),
 }
 
+impl_error!(solve Error);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -299,8 +406,11 @@ L.LLLLL.LL";
     fn parse_input_example() {
         assert_eq!(
             parse_input(EXAMPLE_INPUT).unwrap(),
-            Grid {
-                g: vec![
+            Layout(
+                Grid::new(
+                    10,
+                    10,
+                    vec![
                     Seat(Empty),
                     Floor,
                     Seat(Empty),
@@ -401,23 +511,23 @@ L.LLLLL.LL";
                     Floor,
                     Seat(Empty),
                     Seat(Empty)
-                ],
-                width: 10,
-                height: 10,
-            }
+                    ]
+                )
+                .unwrap()
+            )
         );
     }
 
     #[test]
     fn part_1_example() {
         let mut layout = parse_input(EXAMPLE_INPUT).unwrap();
-        assert_eq!(part_1(&mut layout), 37);
+        assert_eq!(part_1(&mut layout), Ok(37));
     }
 
     #[test]
     fn part_2_example() {
         let mut layout = parse_input(EXAMPLE_INPUT).unwrap();
-        assert_eq!(part_2(&mut layout), 26);
+        assert_eq!(part_2(&mut layout), Ok(26));
     }
 
     #[test]
@@ -443,7 +553,7 @@ L.LLLLL.LL";
 .............
 .L.L.#.#.#.#.
 .............",
-                (2, 2),
+                (1, 2),
                 0,
             ),
             (
@@ -475,11 +585,135 @@ LLLLLLLLL#
             ),
         ];
         for (input, (x, y), expected) in &test_cases {
-            let layout = parse_input(&input).unwrap();
-            assert_eq!(
-                layout.visible_occupied(layout.loc(*x, *y).unwrap()),
-                *expected
-            );
+            let layout = parse_input(input).unwrap();
+            let sim = Simulation::new(&layout.0, &SeatRules::PART_2);
+            assert_eq!(sim.occupied_neighbours(&layout.0, (*x, *y)), Some(*expected));
+        }
+    }
+
+    #[test]
+    fn seat_rules() {
+        let layout = parse_input("#.#\n...\n#.#").unwrap();
+        // Each corner can see 3 others, but none are adjacent
+        let occupied_neighbours = |rules| {
+            Simulation::new(&layout.0, rules).occupied_neighbours(&layout.0, (0, 0))
+        };
+        assert_eq!(occupied_neighbours(&SeatRules::PART_1), Some(0));
+        assert_eq!(occupied_neighbours(&SeatRules::PART_2), Some(3));
+        let short_sighted = SeatRules {
+            max_distance: Some(1),
+            ..SeatRules::PART_2
+        };
+        assert_eq!(occupied_neighbours(&short_sighted), Some(0));
+
+        assert_eq!(layout.clone().simulate(&SeatRules::PART_2), Ok(4));
+    }
+
+    #[test]
+    fn max_rounds() {
+        let mut layout = parse_input(EXAMPLE_INPUT).unwrap();
+        let impatient = SeatRules {
+            max_rounds: 2,
+            ..SeatRules::PART_1
+        };
+        assert_eq!(layout.simulate(&impatient), Err(Error::NoEquilibrium(2)));
+
+        // Corners which can see each other alternate between all occupied and all empty
+        let mut layout = parse_input("L.L\n...\nL.L").unwrap();
+        let crowded = SeatRules {
+            crowding: 3,
+            max_rounds: 100,
+            ..SeatRules::PART_2
+        };
+        assert_eq!(layout.simulate(&crowded), Err(Error::NoEquilibrium(100)));
+    }
+
+    #[test]
+    fn visualise() {
+        let layout = parse_input(EXAMPLE_INPUT).unwrap();
+        let mut recording = Recording::new();
+        assert!(Solver::visualise(&layout, Part::One, &mut recording));
+        // The initial layout and the 5 rounds in which seats change
+        let frames = recording.frames();
+        assert_eq!(frames.len(), 6);
+        assert_eq!(frames[0].to_string(), EXAMPLE_INPUT);
+        let mut stable = layout.clone();
+        stable.simulate(&SeatRules::PART_1).unwrap();
+        assert_eq!(frames[5].to_string(), stable.to_string());
+    }
+
+    /// Straightforward implementation of a round, casting rays from every seat.
+    fn reference_round(grid: &Grid<Location>, rules: &SeatRules) -> Grid<Location> {
+        let mut next = grid.clone();
+        for (pos, l) in grid.iter() {
+            let occupied = grid::ADJACENT
+                .iter()
+                .filter_map(|&dir| {
+                    grid.ray(pos, dir)
+                        .take(rules.range())
+                        .find(|(_, l)| matches!(l, Seat(_)))
+                })
+                .filter(|(_, l)| **l == Seat(Occupied))
+                .count();
+            next[pos] = match (l, occupied) {
+                (Seat(Empty), 0) => Seat(Occupied),
+                (Seat(Occupied), n) if n >= rules.crowding => Seat(Empty),
+                (l, _) => *l,
+            };
         }
+        next
+    }
+
+    /// Pseudo-random layout with roughly 1 floor tile in 4.
+    fn random_layout(width: usize, height: usize) -> Grid<Location> {
+        let mut x: u32 = 1;
+        let cells = (0..width * height)
+            .map(|_| {
+                x ^= x << 13;
+                x ^= x >> 17;
+                x ^= x << 5;
+                match x % 8 {
+                    0 | 1 => Floor,
+                    2 => Seat(Occupied),
+                    _ => Seat(Empty),
+                }
+            })
+            .collect();
+        Grid::new(width, height, cells).unwrap()
+    }
+
+    #[test]
+    fn matches_reference() {
+        let short_sighted = SeatRules {
+            max_distance: Some(2),
+            ..SeatRules::PART_2
+        };
+        for rules in &[SeatRules::PART_1, SeatRules::PART_2, short_sighted] {
+            let mut expected = random_layout(37, 23);
+            let mut sim = Simulation::new(&expected, rules);
+            for _ in 0..50 {
+                expected = reference_round(&expected, rules);
+                sim.round();
+                let mut actual = Layout(expected.clone());
+                for (&cell, &occupied) in sim.seats.iter().zip(&sim.occupied) {
+                    actual.0.cells_mut()[cell] = Seat(if occupied { Occupied } else { Empty });
+                }
+                assert_eq!(actual.0, expected);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg_attr(not(feature = "slowtests"), ignore)]
+    fn large_layout() {
+        // The example tiled to 1000x1000
+        let size = 1000;
+        let example = parse_input(EXAMPLE_INPUT).unwrap().0;
+        let cells = (0..size * size)
+            .map(|i| example[(i % size % 10, i / size % 10)])
+            .collect();
+        let layout = Layout(Grid::new(size, size, cells).unwrap());
+        assert!(part_1(&mut layout.clone()).is_ok());
+        assert!(part_2(&mut layout.clone()).is_ok());
     }
 }
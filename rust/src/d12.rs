@@ -1,3 +1,5 @@
+use crate::parse::{Context, Located, ResultExt};
+use crate::solution::Solution;
 use std::convert::TryFrom;
 use std::ops;
 use Instruction::*;
@@ -7,7 +9,7 @@ use Instruction::*;
 // location). I'm  not forcing it for the sake of reducing code size, however, as this will
 // convolute the logic for each part.
 
-fn part_1(instructions: &Vec<Instruction>) -> u32 {
+fn part_1(instructions: &[Instruction]) -> u32 {
     let mut pos = (0, 0);
     // Start facing east
     let mut dir = (1, 0);
@@ -30,7 +32,7 @@ fn part_1(instructions: &Vec<Instruction>) -> u32 {
     u32::try_from(pos.0.abs() + pos.1.abs()).unwrap() // FIXME
 }
 
-fn part_2(instructions: &Vec<Instruction>) -> u32 {
+fn part_2(instructions: &[Instruction]) -> u32 {
     let mut pos = (0, 0);
     let mut wp = (10, 1);
 
@@ -52,42 +54,62 @@ fn part_2(instructions: &Vec<Instruction>) -> u32 {
     u32::try_from(pos.0.abs() + pos.1.abs()).unwrap() // FIXME
 }
 
-fn parse_input(input: &str) -> Result<Vec<Instruction>, ParseError> {
+fn parse_input(input: &str) -> Result<Vec<Instruction>, Located<ParseError<'_>>> {
+    let ctx = Context::new(input);
     input
         .lines()
         .map(|l| {
-            if l.len() < 2 {
-                Err(ParseError::InvalidInstruction(l))
+            if l.len() < 2 || !l.is_char_boundary(1) {
+                Err(ctx.error(l, ParseError::InvalidInstruction(l)))
             } else {
                 let (cmdpart, valpart) = l.split_at(1);
                 let n = valpart
                     .parse::<i32>()
-                    .map_err(|e| ParseError::InvalidValue(e))?;
+                    .map_err(ParseError::InvalidValue)
+                    .at(&ctx, valpart)?;
+                let angle = || {
+                    TurnAngle::new(n).ok_or_else(|| ctx.error(valpart, ParseError::InvalidAngle(n)))
+                };
                 match cmdpart {
                     "F" => Ok(Forward(n)),
                     "N" => Ok(North(n)),
                     "S" => Ok(South(n)),
                     "E" => Ok(East(n)),
                     "W" => Ok(West(n)),
-                    "L" => Ok(Left(TurnAngle::new(n).ok_or(ParseError::InvalidAngle(n))?)),
-                    "R" => Ok(Right(TurnAngle::new(n).ok_or(ParseError::InvalidAngle(n))?)),
-                    _ => Err(ParseError::InvalidCommand(cmdpart)),
+                    "L" => Ok(Left(angle()?)),
+                    "R" => Ok(Right(angle()?)),
+                    _ => Err(ctx.error(cmdpart, ParseError::InvalidCommand(cmdpart))),
                 }
             }
         })
         .collect()
 }
 
-pub fn run(input: &str) {
-    let instructions = parse_input(input).expect("unable to parse input");
-    println!("Part 1: {}", part_1(&instructions));
-    println!("Part 2: {}", part_2(&instructions));
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Parsed = Vec<Instruction>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+    type ParseError = Located<ParseError<'a>>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1(instructions: &Self::Parsed) -> Self::Answer1 {
+        part_1(instructions)
+    }
+
+    fn part_2(instructions: &Self::Parsed) -> Self::Answer2 {
+        part_2(instructions)
+    }
 }
 
 /// Newtype representing a valid angle for a `Left` or `Right` instruction. Values should only be
 /// multiples of `+-90` as the puzzle is represented using unit grid.
 #[derive(Debug, PartialEq, PartialOrd, Eq, Ord, Copy, Clone)]
-struct TurnAngle(i32);
+pub struct TurnAngle(i32);
 
 impl TurnAngle {
     fn new(angle: i32) -> Option<TurnAngle> {
@@ -118,7 +140,7 @@ impl ops::Neg for TurnAngle {
 }
 
 #[derive(PartialEq, PartialOrd, Eq, Ord, Debug)]
-enum Instruction {
+pub enum Instruction {
     North(i32),
     South(i32),
     East(i32),
@@ -129,13 +151,15 @@ enum Instruction {
 }
 
 #[derive(PartialEq, Eq, Debug)]
-enum ParseError<'a> {
+pub enum ParseError<'a> {
     InvalidInstruction(&'a str),
     InvalidCommand(&'a str),
     InvalidValue(std::num::ParseIntError),
     InvalidAngle(i32),
 }
 
+impl_error!(parse ParseError<'a>);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,4 +1,7 @@
-type Schedule = Vec<(usize, u64)>;
+use crate::parse::{Context, Located, ResultExt};
+use crate::solution::Solution;
+
+pub type Schedule = Vec<(usize, u64)>;
 
 fn part_1(earliest_timestamp: u64, schedule: &Schedule) -> u64 {
     let mut best_bus: Option<u64> = None;
@@ -203,7 +206,7 @@ fn part_2(schedule: &Schedule) -> u64 {
     let mut t = 1;
     let mut step = 1;
     for (i, id) in schedule {
-        while (t + *i as u64) % id != 0 {
+        while !(t + *i as u64).is_multiple_of(*id) {
             t += step;
         }
         step *= id;
@@ -211,44 +214,65 @@ fn part_2(schedule: &Schedule) -> u64 {
     t
 }
 
-fn parse_input(input: &str) -> Result<(u64, Vec<(usize, u64)>), ParseError> {
+fn parse_input(input: &str) -> Result<(u64, Schedule), Located<ParseError>> {
+    let ctx = Context::new(input);
     let mut lines = input.lines();
     let timestamp = lines
         .next()
-        .ok_or(ParseError::MissingTimestamp)?
+        .ok_or_else(|| ctx.error(ctx.end(), ParseError::MissingTimestamp))?;
+    let timestamp = timestamp
         .parse::<u64>()
-        .map_err(|e| ParseError::InvalidTimestamp(e))?;
+        .map_err(ParseError::InvalidTimestamp)
+        .at(&ctx, timestamp)?;
     let schedule = lines
         .next()
-        .ok_or(ParseError::MissingBusIds)?
+        .ok_or_else(|| ctx.error(ctx.end(), ParseError::MissingBusIds))?
         .split(",")
         .enumerate()
         .filter(|(_, s)| *s != "x")
         .map(|(i, s)| {
-            Ok((
-                i,
-                s.parse::<u64>().map_err(|e| ParseError::InvalidBusId(e))?,
-            ))
+            let id = s
+                .parse::<u64>()
+                .map_err(ParseError::InvalidBusId)
+                .at(&ctx, s)?;
+            Ok((i, id))
         })
-        .collect::<Result<Vec<(usize, u64)>, ParseError>>()?;
+        .collect::<Result<Vec<(usize, u64)>, _>>()?;
 
     Ok((timestamp, schedule))
 }
 
-pub fn run(input: &str) {
-    let (earliest_timestamp, schedule) = parse_input(input).expect("unable to parse input");
-    println!("Part 1: {}", part_1(earliest_timestamp, &schedule));
-    println!("Part 2: {}", part_2(&schedule));
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Parsed = (u64, Schedule);
+    type Answer1 = u64;
+    type Answer2 = u64;
+    type ParseError = Located<ParseError>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1((earliest_timestamp, schedule): &Self::Parsed) -> Self::Answer1 {
+        part_1(*earliest_timestamp, schedule)
+    }
+
+    fn part_2((_, schedule): &Self::Parsed) -> Self::Answer2 {
+        part_2(schedule)
+    }
 }
 
 #[derive(PartialEq, Eq, Debug)]
-enum ParseError {
+pub enum ParseError {
     MissingTimestamp,
     MissingBusIds,
     InvalidTimestamp(std::num::ParseIntError),
     InvalidBusId(std::num::ParseIntError),
 }
 
+impl_error!(parse ParseError);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -288,7 +312,7 @@ mod tests {
             (vec![(0, 1789), (1, 37), (2, 47), (3, 1889)], 1202161486),
         ];
         for (schedule, ts) in tests.iter() {
-            assert_eq!(part_2(&schedule), *ts);
+            assert_eq!(part_2(schedule), *ts);
         }
     }
 }
@@ -1,11 +1,13 @@
+use crate::parse::{Context, Located, ResultExt};
+use crate::solution::Solution;
 use crate::str::split_once;
 use std::collections::HashMap;
 use Instruction::*;
 
-fn part_1(program: &Vec<Instruction>) -> u64 {
-    let mut mask = match program.get(0) {
+fn part_1(program: &[Instruction]) -> u64 {
+    let mut mask = match program.first() {
         Some(Mask(m)) => m,
-        _ => panic!("invalid program"), // FIXME
+        _ => unreachable!("program must begin with a mask"), // Checked by `parse_input`
     };
     let mut mem: HashMap<usize, u64> = HashMap::new();
 
@@ -21,10 +23,10 @@ fn part_1(program: &Vec<Instruction>) -> u64 {
     mem.values().sum()
 }
 
-fn part_2(program: &Vec<Instruction>) -> u64 {
-    let mut mask = match program.get(0) {
+fn part_2(program: &[Instruction]) -> u64 {
+    let mut mask = match program.first() {
         Some(Mask(m)) => FloatingBitMask::new(m.src),
-        _ => panic!("invalid program"), // FIXME
+        _ => unreachable!("program must begin with a mask"), // Checked by `parse_input`
     };
     let mut mem: HashMap<u64, u64> = HashMap::new();
 
@@ -42,46 +44,66 @@ fn part_2(program: &Vec<Instruction>) -> u64 {
     mem.values().sum()
 }
 
-fn parse_input(input: &str) -> Result<Vec<Instruction>, ParseError> {
-    // FIXME: Parse this properly - there's many invalid inputs that would get through
+fn parse_input(input: &str) -> Result<Vec<Instruction<'_>>, Located<ParseError<'_>>> {
+    let ctx = Context::new(input);
+    // Masks must be set before any writes to memory
     if !input.starts_with("mask") {
-        return Err(ParseError::InvalidInstruction);
+        return Err(ctx.error(
+            input.lines().next().unwrap_or(input),
+            ParseError::InvalidInstruction,
+        ));
     }
     input
         .lines()
-        .map(|l| match &l[..4] {
-            "mask" => {
-                let mask = &l[7..];
-                if mask.len() != 36 {
-                    Err(ParseError::InvalidMask(mask))
+        .map(|l| {
+            if let Some(mask) = l.strip_prefix("mask = ") {
+                if mask.len() != 36 || !mask.chars().all(|c| matches!(c, 'X' | '0' | '1')) {
+                    Err(ctx.error(mask, ParseError::InvalidMask(mask)))
                 } else {
                     Ok(Mask(BitMask::new(mask)))
                 }
-            }
-            "mem[" => {
-                let (addr, rest) =
-                    split_once(&l[4..], "]").ok_or(ParseError::InvalidInstruction)?;
+            } else if let Some(rest) = l.strip_prefix("mem[") {
+                let (addr, val) = split_once(rest, "] = ")
+                    .ok_or_else(|| ctx.error(l, ParseError::InvalidInstruction))?;
                 let addr = addr
                     .parse::<usize>()
-                    .map_err(|e| ParseError::InvalidAddress(e))?;
-                let val = rest[3..]
+                    .map_err(ParseError::InvalidAddress)
+                    .at(&ctx, addr)?;
+                let val = val
                     .parse::<u64>()
-                    .map_err(|e| ParseError::InvalidValue(e))?;
+                    .map_err(ParseError::InvalidValue)
+                    .at(&ctx, val)?;
                 Ok(Mem(addr, val))
+            } else {
+                Err(ctx.error(l, ParseError::InvalidInstruction))
             }
-            _ => return Err(ParseError::InvalidInstruction),
         })
         .collect()
 }
 
-pub fn run(input: &str) {
-    let program = parse_input(input).expect("unable to parse input");
-    println!("Part 1: {}", part_1(&program));
-    println!("Part 2: {}", part_2(&program));
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Parsed = Vec<Instruction<'a>>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+    type ParseError = Located<ParseError<'a>>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1(program: &Self::Parsed) -> Self::Answer1 {
+        part_1(program)
+    }
+
+    fn part_2(program: &Self::Parsed) -> Self::Answer2 {
+        part_2(program)
+    }
 }
 
 #[derive(PartialEq, PartialOrd, Eq, Ord, Debug)]
-struct BitMask<'a> {
+pub struct BitMask<'a> {
     /// Original mask e.g. `XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X`
     src: &'a str,
     /// `AND` mask derived from `self.src` (see `BitMask.apply`)
@@ -102,7 +124,7 @@ impl<'a> BitMask<'a> {
                         'X' => (and_mask + power, or_mask),
                         '1' => (and_mask + power, or_mask + power),
                         '0' => (and_mask, or_mask),
-                        _ => panic!("invalid mask character"), // FIXME
+                        _ => unreachable!("invalid mask character"), // Checked by `parse_input`
                     }
                 });
         BitMask {
@@ -174,12 +196,12 @@ impl<'a> FloatingBitMask<'a> {
                             combo_masks.push(val | x_bit);
                         }
                         // Just this bit by itself
-                        combo_masks.push(x_bit as u64);
+                        combo_masks.push(x_bit);
                         (or_mask, x_mask + power, combo_masks)
                     }
                     '1' => (or_mask + power, x_mask, combo_masks),
                     '0' => (or_mask, x_mask, combo_masks),
-                    _ => panic!("invalid mask character"), // FIXME
+                    _ => unreachable!("invalid mask character"), // Checked by `parse_input`
                 }
             },
         );
@@ -223,19 +245,21 @@ impl<'a> FloatingBitMask<'a> {
 }
 
 #[derive(PartialEq, PartialOrd, Eq, Ord, Debug)]
-enum Instruction<'a> {
+pub enum Instruction<'a> {
     Mask(BitMask<'a>),
     Mem(usize, u64),
 }
 
 #[derive(PartialEq, Eq, Debug)]
-enum ParseError<'a> {
+pub enum ParseError<'a> {
     InvalidInstruction,
     InvalidMask(&'a str),
     InvalidAddress(std::num::ParseIntError),
     InvalidValue(std::num::ParseIntError),
 }
 
+impl_error!(parse ParseError<'a>);
+
 #[cfg(test)]
 mod tests {
     use super::*;
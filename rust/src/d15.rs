@@ -1,14 +1,17 @@
-fn part_1(start_nums: &Vec<usize>) -> usize {
+use crate::parse::{Context, Located, ResultExt};
+use crate::solution::Solution;
+
+fn part_1(start_nums: &[usize]) -> usize {
     play(start_nums, 2020)
 }
 
-fn part_2(start_nums: &Vec<usize>) -> usize {
+fn part_2(start_nums: &[usize]) -> usize {
     play(start_nums, 30000000)
 }
 
 /// Play rounds of the memory game until `target_turn` is reached. At which point, return the
 /// number spoken on that turn.
-fn play(start_nums: &Vec<usize>, target_turn: usize) -> usize {
+fn play(start_nums: &[usize], target_turn: usize) -> usize {
     // Skip the starting rounds (see turn_history)
     let mut prev = *start_nums.last().unwrap();
     let mut turn = start_nums.len();
@@ -42,25 +45,43 @@ fn play(start_nums: &Vec<usize>, target_turn: usize) -> usize {
     }
 }
 
-fn parse_input(input: &str) -> Result<Vec<usize>, ParseError> {
+fn parse_input(input: &str) -> Result<Vec<usize>, Located<ParseError>> {
+    let ctx = Context::new(input);
     input
         .trim()
         .split(",")
-        .map(|s| s.parse().map_err(|e| ParseError::InvalidNumber(e)))
+        .map(|s| s.parse().map_err(ParseError::InvalidNumber).at(&ctx, s))
         .collect()
 }
 
-pub fn run(input: &str) {
-    let start_nums = parse_input(input).expect("unable to parse input");
-    println!("Part 1: {}", part_1(&start_nums));
-    println!("Part 2: {}", part_2(&start_nums));
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Parsed = Vec<usize>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+    type ParseError = Located<ParseError>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1(start_nums: &Self::Parsed) -> Self::Answer1 {
+        part_1(start_nums)
+    }
+
+    fn part_2(start_nums: &Self::Parsed) -> Self::Answer2 {
+        part_2(start_nums)
+    }
 }
 
 #[derive(PartialEq, Eq, Debug)]
-enum ParseError {
+pub enum ParseError {
     InvalidNumber(std::num::ParseIntError),
 }
 
+impl_error!(parse ParseError);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,3 +1,5 @@
+use crate::parse::{Context, Located, ResultExt};
+use crate::solution::Solution;
 use crate::str::split_once;
 use std::ops::RangeInclusive;
 use std::str::FromStr;
@@ -46,7 +48,7 @@ fn part_2(td: &TicketData) -> u64 {
         })
         .collect();
     // This has [0, 1, ..., 20] pattern described above
-    fields_for_offsets.sort_by(|a, b| a.1.len().cmp(&(b.1.len())));
+    fields_for_offsets.sort_by_key(|a| a.1.len());
     // Only 20 fields so Vec::contains will likely be faster (or at least on par with)
     // than using a HashSet - See benches/d16.rs
     let mut used_fields: Vec<&Field> = Vec::with_capacity(fields_for_offsets.len());
@@ -72,51 +74,68 @@ fn part_2(td: &TicketData) -> u64 {
 }
 
 /// Return whether `x` is a valid value for *any* of `fields`.
-fn valid_for_any(fields: &Vec<Field>, x: &u32) -> bool {
+fn valid_for_any(fields: &[Field], x: &u32) -> bool {
     fields.iter().any(|f| f.contains(x))
 }
 
-fn parse_input(input: &str) -> Result<(Vec<Field>, Ticket, Vec<Ticket>), ParseError> {
-    // FIXME: Please god tidy this up and handle errors better!
+fn parse_input(input: &str) -> Result<TicketData<'_>, Located<ParseError>> {
+    let ctx = Context::new(input);
     let mut sections = input.split("\n\n");
+    let mut next_section = || {
+        sections
+            .next()
+            .ok_or_else(|| ctx.error(ctx.end(), ParseError::MalformedInput))
+    };
 
     // Parse fields
-    let fields = sections
-        .next()
-        .ok_or(ParseError::MalformedInput)?
+    let fields = next_section()?
         .lines()
-        .map(|l| Field::new(l))
-        .collect::<Result<Vec<Field>, ParseError>>()?;
+        .map(|l| Field::new(l).at(&ctx, l))
+        .collect::<Result<Vec<Field>, _>>()?;
 
     // Parse my ticket
-    let my_ticket: Ticket = sections
-        .next()
-        .and_then(|s| s.lines().skip(1).next()) // Skip "your ticket" line
-        .ok_or(ParseError::MalformedInput)?
-        .parse()?;
+    let section = next_section()?;
+    let my_ticket = section
+        .lines()
+        .nth(1) // Skip "your ticket" line
+        .ok_or_else(|| ctx.error(section, ParseError::MalformedInput))?;
+    let my_ticket: Ticket = my_ticket.parse().at(&ctx, my_ticket)?;
 
     // Parse nearby tickets
-    let nearby_tickets: Vec<Ticket> = sections
-        .next()
-        .ok_or(ParseError::MalformedInput)?
+    let nearby_tickets: Vec<Ticket> = next_section()?
         .lines()
         .skip(1)
-        .map(|l| l.parse())
-        .collect::<Result<Vec<Ticket>, ParseError>>()?;
+        .map(|l| l.parse().at(&ctx, l))
+        .collect::<Result<Vec<Ticket>, _>>()?;
 
     Ok((fields, my_ticket, nearby_tickets))
 }
 
-pub fn run(input: &str) {
-    let td = parse_input(input).expect("unable to parse input");
-    println!("Part 1: {}", part_1(&td));
-    println!("Part 2: {}", part_2(&td));
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Parsed = TicketData<'a>;
+    type Answer1 = u32;
+    type Answer2 = u64;
+    type ParseError = Located<ParseError>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1(td: &Self::Parsed) -> Self::Answer1 {
+        part_1(td)
+    }
+
+    fn part_2(td: &Self::Parsed) -> Self::Answer2 {
+        part_2(td)
+    }
 }
 
-type TicketData<'a> = (Vec<Field<'a>>, Ticket, Vec<Ticket>);
+pub type TicketData<'a> = (Vec<Field<'a>>, Ticket, Vec<Ticket>);
 
 #[derive(PartialEq, Eq, Debug, Clone, Hash)]
-struct Ticket(Vec<u32>);
+pub struct Ticket(Vec<u32>);
 
 impl FromStr for Ticket {
     type Err = ParseError;
@@ -162,11 +181,13 @@ impl<'a> Field<'a> {
 }
 
 #[derive(PartialEq, PartialOrd, Eq, Ord, Debug)]
-enum ParseError {
+pub enum ParseError {
     MalformedInput,
     InvalidTicket,
 }
 
+impl_error!(parse ParseError);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -240,7 +261,7 @@ nearby tickets:
 15,1,5
 5,14,9
 500,14,9";
-        let td = parse_input(&input).unwrap();
+        let td = parse_input(input).unwrap();
         assert_eq!(part_2(&td), 132);
     }
 }
@@ -1,10 +1,11 @@
-use crate::char_enum;
+use crate::automaton::{Automaton, Point, Rule};
+use crate::grid::{self, Cell, Grid};
+use crate::parse::Located;
+use crate::solution::{Part, Solution};
+use crate::visualise::{Frame, Recording, BACKGROUND};
 use std::collections::HashSet;
 use CubeState::*;
 
-// FIXME: Remove major duplication from part_1 and part_2 as they differ in their type
-// signature (Coord3 vs Coord4).
-
 /// Observation: Only need to keep track of the coordinates containing active cubes.
 ///
 /// All required information can de derived from knowing the current set of coordinates
@@ -21,160 +22,113 @@ use CubeState::*;
 /// "out"removes the need to grow the grid as the active cubes move "outwards". It is also more
 /// memory efficient as only the active cubes are tracked at any given time rather than all
 /// cubes.
-fn part_1(active_cubes: &HashSet<Coord3>) -> usize {
-    let mut state = active_cubes.clone();
-    for _ in 0..6 {
-        let mut next_state = HashSet::new(); // TODO: Avoid this?
-        let mut inactive = HashSet::new(); // TODO: Avoid this?
-        for c in state.iter() {
-            // Iterate through neighbours
-            // - Update inactive set
-            // - Count active neighbours
-            // Update next_state according to active_neighbours
-            let mut active_neighbours = 0;
-            for n in c.neighbours() {
-                if state.contains(&n) {
-                    active_neighbours += 1;
-                } else {
-                    inactive.insert(n);
-                }
-            }
-
-            // Apply rule 1:
-            // > If a cube is active and exactly 2 or 3 of its neighbors are also active,
-            // > the cube remains active. Otherwise, the cube becomes inactive.
-            if active_neighbours == 2 || active_neighbours == 3 {
-                next_state.insert(*c);
-            } else {
-                inactive.insert(*c);
-            }
-        }
-
-        for c in inactive.iter() {
-            // Count active neighbours & update next_state accordingly
-            let active_neighbours = c.neighbours().filter(|n| state.contains(n)).count();
-
-            // Apply rule 2:
-            // > If a cube is inactive but exactly 3 of its neighbors are active, the cube
-            // > becomes active. Otherwise, the cube remains inactive.
-            if active_neighbours == 3 {
-                next_state.insert(*c);
-            }
-        }
-        // Update all cubes simulataneously
-        state = next_state;
-    }
-    state.len()
+///
+/// Rules:
+/// > If a cube is active and exactly 2 or 3 of its neighbors are also active,
+/// > the cube remains active. Otherwise, the cube becomes inactive.
+///
+/// > If a cube is inactive but exactly 3 of its neighbors are active, the cube
+/// > becomes active. Otherwise, the cube remains inactive.
+fn part_1(active_cubes: &HashSet<Point<3>>) -> usize {
+    boot(active_cubes, &mut Recording::disabled())
 }
 
 /// Exactly the same as `part_1`, just using 4 dimensions.
-fn part_2(active_cubes: &HashSet<Coord4>) -> usize {
-    let mut state = active_cubes.clone();
+fn part_2(active_cubes: &HashSet<Point<4>>) -> usize {
+    boot(active_cubes, &mut Recording::disabled())
+}
+
+/// Run the 6 cycle boot process, recording the initial state and the state after each cycle.
+fn boot<const N: usize>(active_cubes: &HashSet<Point<N>>, recording: &mut Recording) -> usize {
+    let mut automaton = Automaton::new(active_cubes.iter().copied(), Rule::new(&[3], &[2, 3]));
+    recording.record(|| frame(automaton.active()));
     for _ in 0..6 {
-        let mut next_state = HashSet::new(); // TODO: Avoid this?
-        let mut inactive = HashSet::new(); // TODO: Avoid this?
-        for c in state.iter() {
-            // Iterate through neighbours
-            // - Update inactive set
-            // - Count active neighbours
-            // Update next_state according to active_neighbours
-            let mut active_neighbours = 0;
-            for n in c.neighbours() {
-                if state.contains(&n) {
-                    active_neighbours += 1;
-                } else {
-                    inactive.insert(n);
-                }
-            }
+        automaton.step();
+        recording.record(|| frame(automaton.active()));
+    }
+    automaton.len()
+}
 
-            // Apply rule 1:
-            // > If a cube is active and exactly 2 or 3 of its neighbors are also active,
-            // > the cube remains active. Otherwise, the cube becomes inactive.
-            if active_neighbours == 2 || active_neighbours == 3 {
-                next_state.insert(*c);
-            } else {
-                inactive.insert(*c);
-            }
+/// Draw the cubes as in the puzzle description, with one `x`/`y` slice for each `z` from left to
+/// right and (in 4 dimensions) one row of slices for each `w` from top to bottom. Only the
+/// bounding box of the active cubes is drawn.
+fn frame<const N: usize>(active_cubes: &HashSet<Point<N>>) -> Frame {
+    let mut min = [0; 4];
+    let mut max = [0; 4];
+    for (i, Point(p)) in active_cubes.iter().enumerate() {
+        for (d, &x) in p.iter().take(4).enumerate() {
+            min[d] = if i == 0 { x } else { min[d].min(x) };
+            max[d] = if i == 0 { x } else { max[d].max(x) };
         }
-
-        for c in inactive.iter() {
-            // Count active neighbours & update next_state accordingly
-            let active_neighbours = c.neighbours().filter(|n| state.contains(n)).count();
-
-            // Apply rule 2:
-            // > If a cube is inactive but exactly 3 of its neighbors are active, the cube
-            // > becomes active. Otherwise, the cube remains inactive.
-            if active_neighbours == 3 {
-                next_state.insert(*c);
+    }
+    let extent = |d: usize| (max[d] - min[d] + 1) as usize;
+    let (width, height) = (extent(0), extent(1));
+    // Slices are separated by a column/row of background
+    let mut frame = Grid::filled(
+        extent(2) * (width + 1) - 1,
+        extent(3) * (height + 1) - 1,
+        BACKGROUND,
+    );
+    for z in 0..extent(2) {
+        for w in 0..extent(3) {
+            for x in 0..width {
+                for y in 0..height {
+                    frame[(z * (width + 1) + x, w * (height + 1) + y)] = InActive.to_char();
+                }
             }
         }
-        // Update all cubes simulataneously
-        state = next_state;
     }
-    state.len()
-}
-
-fn parse_input(input: &str) -> Result<HashSet<Coord3>, ParseError> {
-    input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, l)| {
-            l.chars()
-                .enumerate()
-                .map(move |(x, c)| match CubeState::new(c) {
-                    Ok(state) => Ok((Coord3(x as i64, y as i64, 0_i64), state)),
-                    Err(_) => Err(ParseError::MalformedInput),
-                })
-        })
-        .filter_map(|x| match x {
-            Ok((coord, Active)) => Some(Ok(coord)),
-            Ok((_, _)) => None,
-            Err(e) => Some(Err(e)),
-        })
-        .collect()
+    for Point(p) in active_cubes {
+        let offset = |d: usize| p.get(d).map_or(0, |x| (x - min[d]) as usize);
+        let pos = (
+            offset(2) * (width + 1) + offset(0),
+            offset(3) * (height + 1) + offset(1),
+        );
+        frame[pos] = Active.to_char();
+    }
+    frame
 }
 
-pub fn run(input: &str) {
-    let initial_state_3d = parse_input(input).expect("unable to parse input");
-    println!("Part 1: {}", part_1(&initial_state_3d));
-
-    let initial_state_4d = initial_state_3d
+fn parse_input(input: &str) -> Result<HashSet<Point<3>>, Located<grid::ParseError>> {
+    let grid: Grid<CubeState> = input.parse()?;
+    Ok(grid
         .iter()
-        .map(|c| Coord4::from_3d(c))
-        .collect();
-    println!("Part 2: {}", part_2(&initial_state_4d));
+        .filter(|(_, &state)| state == Active)
+        .map(|((x, y), _)| Point([x as i64, y as i64, 0]))
+        .collect())
 }
 
-#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Hash, Clone, Copy)]
-struct Coord3(i64, i64, i64);
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Parsed = HashSet<Point<3>>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+    type ParseError = Located<grid::ParseError>;
 
-impl<'a> Coord3 {
-    fn neighbours(&'a self) -> impl Iterator<Item = Coord3> + 'a {
-        (-1..=1)
-            .flat_map(move |dx| (-1..=1).flat_map(move |dy| (-1..=1).map(move |dz| (dx, dy, dz))))
-            .filter(|x| *x != (0, 0, 0))
-            .map(move |(dx, dy, dz)| Coord3(self.0 + dx, self.1 + dy, self.2 + dz))
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
     }
-}
 
-#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Hash, Clone, Copy)]
-struct Coord4(i64, i64, i64, i64);
+    fn part_1(initial_state_3d: &Self::Parsed) -> Self::Answer1 {
+        part_1(initial_state_3d)
+    }
 
-impl<'a> Coord4 {
-    fn from_3d(c: &Coord3) -> Self {
-        Self(c.0, c.1, c.2, 0)
+    fn part_2(initial_state_3d: &Self::Parsed) -> Self::Answer2 {
+        let initial_state_4d = initial_state_3d.iter().map(|c| c.embed()).collect();
+        part_2(&initial_state_4d)
     }
 
-    fn neighbours(&'a self) -> impl Iterator<Item = Coord4> + 'a {
-        // TODO: Surely there's a better way to do this...
-        (-1..=1)
-            .flat_map(move |dx| {
-                (-1..=1).flat_map(move |dy| {
-                    (-1..=1).flat_map(move |dz| (-1..=1).map(move |dw| (dx, dy, dz, dw)))
-                })
-            })
-            .filter(|x| *x != (0, 0, 0, 0))
-            .map(move |(dx, dy, dz, dw)| Coord4(self.0 + dx, self.1 + dy, self.2 + dz, self.3 + dw))
+    fn visualise(initial_state_3d: &Self::Parsed, part: Part, recording: &mut Recording) -> bool {
+        match part {
+            Part::One => boot(initial_state_3d, recording),
+            Part::Two => {
+                let initial_state_4d: HashSet<Point<4>> =
+                    initial_state_3d.iter().map(|c| c.embed()).collect();
+                boot(&initial_state_4d, recording)
+            }
+        };
+        true
     }
 }
 
@@ -185,11 +139,6 @@ char_enum! {
     }
 }
 
-#[derive(PartialEq, PartialOrd, Eq, Ord, Debug)]
-enum ParseError {
-    MalformedInput,
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -201,12 +150,12 @@ mod tests {
 
     #[test]
     fn parse_input_example() {
-        let expected_coords: [Coord3; 5] = [
-            Coord3(1, 0, 0),
-            Coord3(2, 1, 0),
-            Coord3(0, 2, 0),
-            Coord3(1, 2, 0),
-            Coord3(2, 2, 0),
+        let expected_coords: [Point<3>; 5] = [
+            Point([1, 0, 0]),
+            Point([2, 1, 0]),
+            Point([0, 2, 0]),
+            Point([1, 2, 0]),
+            Point([2, 2, 0]),
         ];
         assert_eq!(
             parse_input(EXAMPLE_INPUT).unwrap(),
@@ -223,11 +172,29 @@ mod tests {
     #[test]
     fn part_2_example() {
         let initial_state_3d = parse_input(EXAMPLE_INPUT).unwrap();
-        let initial_state_4d: HashSet<Coord4> = initial_state_3d
-            .iter()
-            .map(|c| Coord4::from_3d(c))
-            .collect();
+        let initial_state_4d: HashSet<Point<4>> =
+            initial_state_3d.iter().map(|c| c.embed()).collect();
 
         assert_eq!(part_2(&initial_state_4d), 848);
     }
+
+    #[test]
+    fn visualise() {
+        let initial_state_3d = parse_input(EXAMPLE_INPUT).unwrap();
+        let mut recording = Recording::new();
+        assert!(Solver::visualise(&initial_state_3d, Part::One, &mut recording));
+        let frames = recording.frames();
+        assert_eq!(frames.len(), 7);
+        assert_eq!(frames[0].to_string(), EXAMPLE_INPUT);
+        // z = -1, 0 and 1 after 1 cycle
+        assert_eq!(
+            frames[1].to_string(),
+            "#.. #.# #..\n..# .## ..#\n.#. .#. .#."
+        );
+
+        let mut recording = Recording::new();
+        assert!(Solver::visualise(&initial_state_3d, Part::Two, &mut recording));
+        // w = -1, 0 and 1 from top to bottom
+        assert_eq!(recording.frames()[1].height(), 3 * 4 - 1);
+    }
 }
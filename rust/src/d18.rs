@@ -1,192 +1,646 @@
-use crate::dbg_eprintln;
+use crate::parse::{Context, Located};
+use crate::solution::Solution;
+use std::convert::TryFrom;
+use std::fmt;
+use Assoc::*;
 use OpType::*;
 use Token::*;
 
-// TODO: Plenty of room for improvement - better error handling, reducing allocations during
-// eval/parsing e.t.c but this works correctly and (on my laptop at least) executes my puzzle input
-// ,to the human eye, instantaneously.
+/// Key points:
+/// - All operators have the *same* precedence
+/// - Operators are evaluated left to right
+///
+/// Parse each expression with an `OpTable` describing these rules and evaluate the resulting
+/// `Expr`s.
+fn part_1(exprs: &[Vec<Token>]) -> Result<Value, Error> {
+    sum(exprs, &OpTable::PART_1)
+}
 
-// TODO: return Result?
-fn part_1(exprs: &[Vec<Token>]) -> u64 {
-    let getop: OpLookup = |op: &OpType| match op {
-        Add => (|x, y| x + y, 0),
-        Mul => (|x, y| x * y, 0),
-    };
+/// Key points:
+/// - Addition has a *higher* precedence than multiplication
+///
+/// Exactly the same as `part_1`, just with a different `OpTable`.
+fn part_2(exprs: &[Vec<Token>]) -> Result<Value, Error> {
+    sum(exprs, &OpTable::PART_2)
+}
+
+/// Numeric type answers are calculated with: `i64` with overflow reported as an error, or
+/// arbitrary precision integers with the `bigint` feature.
+#[cfg(not(feature = "bigint"))]
+pub type Value = i64;
+#[cfg(feature = "bigint")]
+pub type Value = num_bigint::BigInt;
+
+/// Sum of the values of `exprs` when parsed according to `table`.
+fn sum<N: Number>(exprs: &[Vec<Token>], table: &OpTable) -> Result<N, Error> {
+    exprs.iter().try_fold(N::from(0), |acc, tokens| {
+        let expr = Expr::parse(tokens, table).map_err(|e| Error::Syntax(e.error))?;
+        let x = expr.evaluate()?;
+        acc.apply(Add, &x)
+            .ok_or_else(|| Error::Overflow(format!("{} + {}", acc, expr)))
+    })
+}
+
+/// Integer type which expressions can be evaluated with.
+///
+/// Operations return `None` if the result cannot be represented. Division by zero and negative
+/// exponents are checked before calling `apply`.
+pub trait Number: From<i64> + PartialOrd + Clone + fmt::Display {
+    fn negate(&self) -> Option<Self>;
+
+    /// `self op other`, with division rounding towards zero.
+    fn apply(&self, op: OpType, other: &Self) -> Option<Self>;
+}
+
+impl Number for i64 {
+    fn negate(&self) -> Option<Self> {
+        i64::checked_neg(*self)
+    }
+
+    fn apply(&self, op: OpType, other: &Self) -> Option<Self> {
+        let (x, y) = (*self, *other);
+        match op {
+            Add => x.checked_add(y),
+            Sub => x.checked_sub(y),
+            Mul => x.checked_mul(y),
+            Div => x.checked_div(y),
+            Pow => u32::try_from(y).ok().and_then(|y| x.checked_pow(y)),
+        }
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl Number for num_bigint::BigInt {
+    fn negate(&self) -> Option<Self> {
+        Some(-self)
+    }
 
-    exprs
-        .iter()
-        .try_fold(0, |acc, e| evaluate(&e, getop).map(|x| acc + x))
-        .unwrap()
+    fn apply(&self, op: OpType, other: &Self) -> Option<Self> {
+        match op {
+            Add => Some(self + other),
+            Sub => Some(self - other),
+            Mul => Some(self * other),
+            Div => Some(self / other),
+            // Exponents which don't fit in a `u32` would run out of memory anyway
+            Pow => u32::try_from(other).ok().map(|y| self.pow(y)),
+        }
+    }
+}
+
+/// Precedence and associativity of operators, used to decide how to group an expression.
+///
+/// Operators with a higher precedence bind more tightly e.g. if `*` has a higher precedence
+/// than `+` then `1 + 2 * 3` is `1 + (2 * 3)`. Associativity decides how to group operators of
+/// the same precedence e.g. if `-` is left associative then `3 - 2 - 1` is `(3 - 2) - 1`.
+///
+/// Operators which are not in the table are syntax errors.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct OpTable {
+    /// Precedence and associativity of each binary operator, indexed by `OpType`
+    binary: [Option<(u8, Assoc)>; 5],
+    /// Precedence of unary minus
+    negation: Option<u8>,
 }
 
-// TODO: return Result?
-fn part_2(exprs: &[Vec<Token>]) -> u64 {
-    let getop: OpLookup = |op: &OpType| match op {
-        Add => (|x, y| x + y, 1),
-        Mul => (|x, y| x * y, 0),
+impl OpTable {
+    /// The rules of part 1: every binary operator has the same precedence and is evaluated left
+    /// to right.
+    pub const PART_1: OpTable = OpTable {
+        binary: [Some((1, Left)); 5],
+        negation: Some(2),
+    };
+
+    /// The rules of part 2: addition and subtraction before multiplication and division. The
+    /// other operators are as in `STANDARD`.
+    pub const PART_2: OpTable = OpTable {
+        binary: [
+            Some((2, Left)),
+            Some((2, Left)),
+            Some((1, Left)),
+            Some((1, Left)),
+            Some((4, Right)),
+        ],
+        negation: Some(3),
+    };
+
+    /// The usual rules of arithmetic: exponentiation (right associative), then negation, then
+    /// multiplication and division, then addition and subtraction.
+    pub const STANDARD: OpTable = OpTable {
+        binary: [
+            Some((1, Left)),
+            Some((1, Left)),
+            Some((2, Left)),
+            Some((2, Left)),
+            Some((4, Right)),
+        ],
+        negation: Some(3),
     };
 
-    exprs
-        .iter()
-        .try_fold(0, |acc, e| evaluate(&e, getop).map(|x| acc + x))
-        .unwrap()
+    /// A table without any operators, to add operators to with `binary` and `negation`.
+    pub fn empty() -> Self {
+        OpTable {
+            binary: [None; 5],
+            negation: None,
+        }
+    }
+
+    /// Add (or replace) binary operators `ops`.
+    pub fn binary(mut self, ops: &[OpType], precedence: u8, assoc: Assoc) -> Self {
+        for &op in ops {
+            self.binary[op as usize] = Some((precedence, assoc));
+        }
+        self
+    }
+
+    /// Add (or replace) unary minus.
+    pub fn negation(mut self, precedence: u8) -> Self {
+        self.negation = Some(precedence);
+        self
+    }
+
+    pub fn get(&self, op: OpType) -> Option<(u8, Assoc)> {
+        self.binary[op as usize]
+    }
+
+    /// Precedence of the (sub-)expression with `node` at its root, for deciding whether it needs
+    /// parentheses.
+    fn precedence(&self, node: Node) -> u16 {
+        match node {
+            Node::Int(_) => u16::MAX,
+            Node::Neg => self.negation.map_or(0, u16::from),
+            Node::Binary(op) => self.get(op).map_or(0, |(p, _)| p.into()),
+        }
+    }
 }
 
-/// Evaluate an infix expression `expr` to a single value.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Assoc {
+    Left,
+    Right,
+}
+
+/// Abstract syntax tree of an expression. Parentheses are implied by the structure of the tree.
 ///
-/// `expr` is first transformed into postfix notation via a simplified (no operator
-/// associativity) shunting-yard algorithm before the resulting expression is evaluated.
-fn evaluate(expr: &[Token], getop: OpLookup) -> Result<u64, ParseError> {
-    dbg_eprintln!("expr (infix): {:?}", expr);
-    // Shunting-yard algorithm
-    let mut outq: Vec<Token> = vec![]; // Queue of values/operators in postfix notation
-    let mut opstack: Vec<Token> = vec![]; // Stack of operators being processed
-    for t in expr {
-        dbg_eprintln!("t: {:?}", t);
-        match t {
-            Int(_) => outq.push(*t),
-            LParen => opstack.push(*t),
-            Operator(optype) => {
-                let (_, op_precedence) = getop(optype);
-                while let Some(top) = opstack.last() {
-                    dbg_eprintln!("top: {:?}", top);
-                    match top {
-                        LParen => break,
-                        // Note: Normal shunting-yard alogrithm would take associativity into
-                        // account here. Since both possible operators have the same (left)
-                        // associativity this has been removed.
-                        Operator(top_optype) => {
-                            let (_, top_op_precedence) = getop(top_optype);
-                            if top_op_precedence >= op_precedence {
-                                outq.push(opstack.pop().unwrap());
-                            } else {
-                                break;
-                            }
+/// The nodes of the tree are stored in postfix order: the operands of each operator are the
+/// sub-trees immediately before it. Lines can be arbitrarily long (or deeply nested) so every
+/// operation on the tree is a loop over the nodes, rather than recursing into sub-trees and
+/// risking overflowing the stack.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Expr {
+    nodes: Vec<Node>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum Node {
+    Int(i64),
+    /// Unary minus
+    Neg,
+    Binary(OpType),
+}
+
+/// Part of an expression still to be written by `Expr::write_infix`.
+enum Piece {
+    /// The sub-tree rooted at a node, along with its parent and whether it is the right operand
+    Node(usize, Option<(usize, bool)>),
+    Op(OpType),
+    Text(&'static str),
+}
+
+impl Expr {
+    /// Parse a complete expression from `tokens`, grouping operators according to `table`.
+    ///
+    /// Uses the [shunting yard algorithm](https://en.wikipedia.org/wiki/Shunting_yard_algorithm):
+    /// operands are output as they are read, while operators wait on a stack until an operator
+    /// which binds less tightly (or the end of the enclosing parentheses) is read, at which point
+    /// their operands have been output and they can be too.
+    pub fn parse(tokens: &[Token], table: &OpTable) -> Result<Expr, SyntaxError> {
+        let error = |error, token| SyntaxError { error, token };
+        let mut nodes = vec![];
+        let mut ops: Vec<Pending> = vec![];
+        let mut expect_operand = true;
+        for (pos, &token) in tokens.iter().enumerate() {
+            match (expect_operand, token) {
+                (true, Int(x)) => {
+                    nodes.push(Node::Int(x));
+                    expect_operand = false;
+                }
+                (true, LParen) => ops.push(Pending::LParen(pos)),
+                (true, Operator(Sub)) => {
+                    let precedence = table
+                        .negation
+                        .ok_or_else(|| error(ParseError::UnsupportedOperator(Sub), pos))?;
+                    ops.push(Pending::Neg(precedence.into()));
+                }
+                (true, _) => return Err(error(ParseError::UnexpectedToken, pos)),
+                (false, Operator(op)) => {
+                    let (precedence, assoc) = table
+                        .get(op)
+                        .ok_or_else(|| error(ParseError::UnsupportedOperator(op), pos))?;
+                    let precedence = u16::from(precedence);
+                    // Output the operators which `op` is not part of the (right) operand of
+                    while let Some(&top) = ops.last() {
+                        let min = match top {
+                            Pending::Binary(_, p, Left) => p + 1,
+                            Pending::Binary(_, p, Right) | Pending::Neg(p) => p,
+                            Pending::LParen(_) => break,
+                        };
+                        if precedence >= min {
+                            break;
                         }
-                        _ => return Err(ParseError::InvalidExpression),
+                        nodes.extend(ops.pop().and_then(Pending::node));
                     }
+                    ops.push(Pending::Binary(op, precedence, assoc));
+                    expect_operand = true;
                 }
-                opstack.push(*t);
+                (false, RParen) => loop {
+                    match ops.pop() {
+                        Some(Pending::LParen(_)) => break,
+                        Some(op) => nodes.extend(op.node()),
+                        None => return Err(error(ParseError::UnmatchedParen, pos)),
+                    }
+                },
+                (false, _) => return Err(error(ParseError::UnexpectedToken, pos)),
             }
-            RParen => {
-                while let Some(op) = opstack.pop().filter(|op| *op != LParen) {
-                    outq.push(op);
-                }
+        }
+        if expect_operand {
+            return Err(error(ParseError::UnexpectedEnd, tokens.len()));
+        }
+        while let Some(op) = ops.pop() {
+            match op {
+                Pending::LParen(pos) => return Err(error(ParseError::UnmatchedParen, pos)),
+                op => nodes.extend(op.node()),
             }
         }
+        Ok(Expr { nodes })
     }
 
-    while let Some(op) = opstack.pop().filter(|op| *op != LParen) {
-        outq.push(op)
+    /// Value of the expression, calculated with `N`. Division rounds towards zero.
+    ///
+    /// An operation whose result does not fit in `N` is an `Error::Overflow` naming the
+    /// innermost sub-expression which overflowed.
+    pub fn evaluate<N: Number>(&self) -> Result<N, Error> {
+        // Values of the sub-trees which are yet to be used as operands, along with the index of
+        // the first node of each
+        let mut values: Vec<(N, usize)> = vec![];
+        for (i, &node) in self.nodes.iter().enumerate() {
+            let (result, start) = match node {
+                Node::Int(x) => (Some(N::from(x)), i),
+                Node::Neg => {
+                    let (x, start) = values.pop().expect("missing operand");
+                    (x.negate(), start)
+                }
+                Node::Binary(op) => {
+                    let (y, _) = values.pop().expect("missing operand");
+                    let (x, start) = values.pop().expect("missing operand");
+                    let zero = N::from(0);
+                    match op {
+                        Div if y == zero => return Err(Error::DivisionByZero),
+                        Pow if y < zero => return Err(Error::NegativeExponent),
+                        _ => (x.apply(op, &y), start),
+                    }
+                }
+            };
+            let overflow = || {
+                let sub_expr = Expr {
+                    nodes: self.nodes[start..=i].to_vec(),
+                };
+                Error::Overflow(sub_expr.to_string())
+            };
+            values.push((result.ok_or_else(overflow)?, start));
+        }
+        Ok(values.pop().expect("empty expression").0)
     }
 
-    // Unprocessed tokens -> malformed input
-    if !opstack.is_empty() {
-        return Err(ParseError::InvalidExpression);
+    /// The expression with only the parentheses needed for it to parse back to the same tree
+    /// under `table` e.g. `1 + 2 * 3` is `(1 + 2) * 3` under `OpTable::STANDARD`.
+    pub fn pretty(&self, table: &OpTable) -> String {
+        let mut s = String::new();
+        self.write_infix(&mut s, |i, parent| {
+            let (parent, right) = match parent {
+                Some(p) => p,
+                None => return false,
+            };
+            let p = table.precedence(self.nodes[parent]);
+            let child = table.precedence(self.nodes[i]);
+            match self.nodes[parent] {
+                Node::Binary(op) => {
+                    let assoc = table.get(op).map(|(_, a)| a);
+                    let side = if right { Right } else { Left };
+                    child < p || (child == p && assoc != Some(side))
+                }
+                _ => child < p,
+            }
+        })
+        .unwrap();
+        s
     }
 
-    dbg_eprintln!("outq (postfix): {:?}", outq);
+    /// The expression in postfix (reverse Polish) notation e.g. `1 + 2 * 3` is `1 2 3 * +` under
+    /// `OpTable::STANDARD`. Unary minus is written as `neg`.
+    pub fn postfix(&self) -> String {
+        let words: Vec<String> = self
+            .nodes
+            .iter()
+            .map(|node| match node {
+                Node::Int(x) => x.to_string(),
+                Node::Neg => "neg".to_string(),
+                Node::Binary(op) => op.to_string(),
+            })
+            .collect();
+        words.join(" ")
+    }
 
-    // Evaluate postfix expression in outq
-    let mut stack = vec![];
-    for t in outq {
-        dbg_eprintln!("t: {:?}", t);
-        match t {
-            Int(x) => stack.push(x),
-            // TODO: Assign a function to operators directly to avoid this duplication?
-            Operator(op) => {
-                if let (Some(y), Some(x)) = (stack.pop(), stack.pop()) {
-                    let (f, _) = getop(&op);
-                    stack.push(f(x, y));
-                } else {
-                    // Missing operand
-                    return Err(ParseError::InvalidExpression);
+    /// The tree with a line for each node, indented by its depth.
+    pub fn dump(&self) -> String {
+        let starts = self.starts();
+        let mut lines = vec![];
+        let mut stack = vec![(self.nodes.len() - 1, 0)];
+        while let Some((i, depth)) = stack.pop() {
+            let indent = "  ".repeat(depth);
+            match self.nodes[i] {
+                Node::Int(x) => lines.push(format!("{}{}", indent, x)),
+                Node::Neg => {
+                    lines.push(format!("{}Neg", indent));
+                    stack.push((i - 1, depth + 1));
+                }
+                Node::Binary(op) => {
+                    lines.push(format!("{}{:?}", indent, op));
+                    stack.push((i - 1, depth + 1));
+                    stack.push((starts[i - 1] - 1, depth + 1));
                 }
             }
-            _ => unreachable!(),
         }
+        lines.join("\n")
     }
 
-    assert_eq!(stack.len(), 1);
-    Ok(stack.pop().unwrap())
+    /// Index of the first node of the sub-tree rooted at each node.
+    fn starts(&self) -> Vec<usize> {
+        let mut starts = Vec::with_capacity(self.nodes.len());
+        for (i, node) in self.nodes.iter().enumerate() {
+            let start = match node {
+                Node::Int(_) => i,
+                Node::Neg => starts[i - 1],
+                // The right operand ends just before the operator, and the left operand just
+                // before that
+                Node::Binary(_) => starts[starts[i - 1] - 1],
+            };
+            starts.push(start);
+        }
+        starts
+    }
+
+    /// Write the expression in infix notation, with parentheses around the sub-tree rooted at
+    /// node `i` if `parens(i, parent)`, where `parent` is the parent of `i` and whether `i` is
+    /// its right operand.
+    fn write_infix<W: fmt::Write>(
+        &self,
+        w: &mut W,
+        parens: impl Fn(usize, Option<(usize, bool)>) -> bool,
+    ) -> fmt::Result {
+        let starts = self.starts();
+        let mut stack = vec![Piece::Node(self.nodes.len() - 1, None)];
+        while let Some(piece) = stack.pop() {
+            match piece {
+                Piece::Node(i, parent) => {
+                    if parens(i, parent) {
+                        w.write_char('(')?;
+                        stack.push(Piece::Text(")"));
+                    }
+                    match self.nodes[i] {
+                        Node::Int(x) => write!(w, "{}", x)?,
+                        Node::Neg => {
+                            w.write_char('-')?;
+                            stack.push(Piece::Node(i - 1, Some((i, true))));
+                        }
+                        Node::Binary(op) => {
+                            stack.push(Piece::Node(i - 1, Some((i, true))));
+                            stack.push(Piece::Op(op));
+                            stack.push(Piece::Node(starts[i - 1] - 1, Some((i, false))));
+                        }
+                    }
+                }
+                Piece::Op(op) => write!(w, " {} ", op)?,
+                Piece::Text(s) => w.write_str(s)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Fully parenthesised, independent of any `OpTable`.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_infix(f, |i, _| matches!(self.nodes[i], Node::Binary(_)))
+    }
 }
 
-fn parse_input(input: &str) -> Result<Vec<Vec<Token>>, ParseError> {
+/// An operator (or opening parenthesis) waiting for its operands to be parsed.
+#[derive(Debug, Clone, Copy)]
+enum Pending {
+    Binary(OpType, u16, Assoc),
+    /// Unary minus, with its precedence
+    Neg(u16),
+    /// Index of the token
+    LParen(usize),
+}
+
+impl Pending {
+    fn node(self) -> Option<Node> {
+        match self {
+            Pending::Binary(op, _, _) => Some(Node::Binary(op)),
+            Pending::Neg(_) => Some(Node::Neg),
+            Pending::LParen(_) => None,
+        }
+    }
+}
+
+/// A syntax error at the `token`th token of an expression (or the end of the expression if
+/// `token` is the number of tokens).
+#[derive(PartialEq, Eq, Debug)]
+pub struct SyntaxError {
+    pub error: ParseError,
+    pub token: usize,
+}
+
+/// Split each line of `input` into `Token`s, checking that each is a well formed expression.
+fn parse_input(input: &str) -> Result<Vec<Vec<Token>>, Located<ParseError>> {
+    let ctx = Context::new(input);
     input
         .lines()
         .map(|l| {
-            let mut r = vec![];
-            let mut chars = l.chars().peekable();
-            while let Some(&c) = chars.peek() {
-                match c {
-                    // Basic symbols
-                    '+' => {
-                        r.push(Operator(Add));
-                        chars.next();
-                    }
-                    '*' => {
-                        r.push(Operator(Mul));
-                        chars.next();
-                    }
-                    '(' => {
-                        r.push(LParen);
-                        chars.next();
-                    }
-                    ')' => {
-                        r.push(RParen);
-                        chars.next();
-                    }
-                    // Whitespace is insignificant -> skip
-                    ' ' => {
-                        chars.next();
-                    }
-                    // Integer (string of consecutive base 10 digits)
-                    _ if c.is_digit(10) => {
-                        chars.next();
-                        let mut x = c.to_digit(10).unwrap() as u64;
-                        while let Some(Some(digit)) = chars.peek().map(|c| c.to_digit(10)) {
-                            x = x * 10 + digit as u64;
+            let (tokens, spans): (Vec<Token>, Vec<&str>) = tokenize(&ctx, l)?.into_iter().unzip();
+            // Every operator is supported by the standard table, and the grouping of operators
+            // does not affect whether an expression is well formed.
+            Expr::parse(&tokens, &OpTable::STANDARD)
+                .map_err(|e| locate_syntax_error(&ctx, l, &spans, e))?;
+            Ok(tokens)
+        })
+        .collect()
+}
+
+/// Parse the single expression `line` according to `table`.
+pub fn parse_line(
+    ctx: &Context<'_>,
+    line: &str,
+    table: &OpTable,
+) -> Result<Expr, Located<ParseError>> {
+    let (tokens, spans): (Vec<Token>, Vec<&str>) = tokenize(ctx, line)?.into_iter().unzip();
+    Expr::parse(&tokens, table).map_err(|e| locate_syntax_error(ctx, line, &spans, e))
+}
+
+/// Locate `error` at the span of the offending token, or the end of `line` if the expression
+/// ended too early.
+fn locate_syntax_error(
+    ctx: &Context<'_>,
+    line: &str,
+    spans: &[&str],
+    error: SyntaxError,
+) -> Located<ParseError> {
+    let span = spans
+        .get(error.token)
+        .copied()
+        .unwrap_or(&line[line.len()..]);
+    ctx.error(span, error.error)
+}
+
+/// The tokens of a single expression, along with the span of `line` of each token.
+pub fn tokenize<'a>(
+    ctx: &Context<'_>,
+    line: &'a str,
+) -> Result<Vec<(Token, &'a str)>, Located<ParseError>> {
+    let mut r = vec![];
+    let mut chars = line.char_indices().peekable();
+    while let Some(&(i, c)) = chars.peek() {
+        let token = match c {
+            // Basic symbols
+            '+' => Operator(Add),
+            '-' => Operator(Sub),
+            '*' => Operator(Mul),
+            '/' => Operator(Div),
+            '^' => Operator(Pow),
+            '(' => LParen,
+            ')' => RParen,
+            // Whitespace is insignificant -> skip
+            ' ' => {
+                chars.next();
+                continue;
+            }
+            // Integer (string of consecutive base 10 digits)
+            _ if c.is_ascii_digit() => {
+                chars.next();
+                let mut x = Some(c.to_digit(10).unwrap() as i64);
+                let mut end = i + 1;
+                while let Some(&(j, d)) = chars.peek() {
+                    match d.to_digit(10) {
+                        Some(digit) => {
+                            x = x
+                                .and_then(|x| x.checked_mul(10))
+                                .and_then(|x| x.checked_add(digit as i64));
+                            end = j + 1;
                             chars.next();
                         }
-                        r.push(Int(x))
+                        None => break,
                     }
-                    _ => return Err(ParseError::InvalidCharacter(c)),
                 }
+                let span = &line[i..end];
+                let x = x.ok_or_else(|| ctx.error(span, ParseError::IntegerTooLarge))?;
+                r.push((Int(x), span));
+                continue;
             }
-            Ok(r)
-        })
-        .collect()
+            _ => {
+                return Err(ctx.error(
+                    &line[i..i + c.len_utf8()],
+                    ParseError::InvalidCharacter(c),
+                ))
+            }
+        };
+        chars.next();
+        r.push((token, &line[i..i + 1]));
+    }
+    Ok(r)
 }
 
-pub fn run(input: &str) {
-    let tokens = parse_input(input).expect("unable to parse input");
-    println!("Part 1: {}", part_1(&tokens));
-    println!("Part 2: {}", part_2(&tokens));
-}
+pub struct Solver;
 
-/// Returns a tuple of `(apply_fn, precedence)` for a given `OpType`.
-type OpLookup = fn(&OpType) -> (fn(u64, u64) -> u64, u8);
+impl<'a> Solution<'a> for Solver {
+    type Parsed = Vec<Vec<Token>>;
+    type Answer1 = Result<Value, Error>;
+    type Answer2 = Result<Value, Error>;
+    type ParseError = Located<ParseError>;
 
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1(exprs: &Self::Parsed) -> Self::Answer1 {
+        part_1(exprs)
+    }
+
+    fn part_2(exprs: &Self::Parsed) -> Self::Answer2 {
+        part_2(exprs)
+    }
+}
+
+/// Binary operators. `Sub` is also unary minus.
 #[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone, Copy)]
-enum OpType {
+pub enum OpType {
     Add,
+    Sub,
     Mul,
+    Div,
+    Pow,
+}
+
+impl fmt::Display for OpType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            Add => '+',
+            Sub => '-',
+            Mul => '*',
+            Div => '/',
+            Pow => '^',
+        };
+        write!(f, "{}", symbol)
+    }
 }
 
 #[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone, Copy)]
-enum Token {
-    Int(u64),
+pub enum Token {
+    Int(i64),
     Operator(OpType),
     LParen,
     RParen,
 }
 
-#[derive(PartialEq, PartialOrd, Eq, Ord, Debug)]
-enum ParseError {
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone, Copy)]
+pub enum ParseError {
     InvalidCharacter(char),
-    InvalidExpression,
+    /// Integer literal which does not fit in an `i64`
+    IntegerTooLarge,
+    /// Token which cannot appear at its position e.g. an operator where an operand is expected
+    UnexpectedToken,
+    /// Expected an operand but the expression ended
+    UnexpectedEnd,
+    /// Parenthesis without a matching opening/closing parenthesis
+    UnmatchedParen,
+    /// Operator which is not in the `OpTable`
+    UnsupportedOperator(OpType),
+}
+
+impl_error!(parse ParseError);
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum Error {
+    /// An expression could not be parsed with the `OpTable` used to solve a part
+    Syntax(ParseError),
+    DivisionByZero,
+    NegativeExponent,
+    /// The result of the (fully parenthesised) sub-expression does not fit in the numeric type
+    Overflow(String),
 }
 
+impl_error!(solve Error);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -199,8 +653,8 @@ mod tests {
         "5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))",
         "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2",
     ];
-    const PART_1_EXAMPLE_ANSWERS: [u64; 6] = [71, 51, 26, 437, 12240, 13632];
-    const PART_2_EXAMPLE_ANSWERS: [u64; 6] = [231, 51, 46, 1445, 669060, 23340];
+    const PART_1_EXAMPLE_ANSWERS: [i64; 6] = [71, 51, 26, 437, 12240, 13632];
+    const PART_2_EXAMPLE_ANSWERS: [i64; 6] = [231, 51, 46, 1445, 669060, 23340];
 
     #[test]
     fn parse_input_examples() {
@@ -331,7 +785,7 @@ mod tests {
     fn part_1_examples() {
         for (input, expected) in EXAMPLE_INPUTS.iter().zip(&PART_1_EXAMPLE_ANSWERS) {
             let exprs = parse_input(input).unwrap();
-            assert_eq!(part_1(&exprs), *expected);
+            assert_eq!(part_1(&exprs), Ok(Value::from(*expected)));
         }
     }
 
@@ -342,14 +796,14 @@ mod tests {
         let single_input = EXAMPLE_INPUTS.join("\n");
         let exprs = parse_input(&single_input).unwrap();
         // Sum of individual expression results given in puzzle
-        assert_eq!(part_1(&exprs), 26457);
+        assert_eq!(part_1(&exprs), Ok(Value::from(26457)));
     }
 
     #[test]
     fn part_2_examples() {
         for (input, expected) in EXAMPLE_INPUTS.iter().zip(&PART_2_EXAMPLE_ANSWERS) {
             let exprs = parse_input(input).unwrap();
-            assert_eq!(part_2(&exprs), *expected);
+            assert_eq!(part_2(&exprs), Ok(Value::from(*expected)));
         }
     }
 
@@ -360,6 +814,204 @@ mod tests {
         let single_input = EXAMPLE_INPUTS.join("\n");
         let exprs = parse_input(&single_input).unwrap();
         // Sum of individual expression results given in puzzle
-        assert_eq!(part_2(&exprs), 694173);
+        assert_eq!(part_2(&exprs), Ok(Value::from(694173)));
+    }
+
+    fn parse(s: &str, table: &OpTable) -> Expr {
+        Expr::parse(&parse_input(s).unwrap()[0], table).unwrap()
+    }
+
+    fn eval(s: &str, table: &OpTable) -> Result<i64, Error> {
+        parse(s, table).evaluate()
+    }
+
+    #[test]
+    fn parse_input_invalid() {
+        let error = |input| {
+            let e = parse_input(input).unwrap_err();
+            (e.error, e.location.unwrap().col)
+        };
+        assert_eq!(error("1 + x"), (ParseError::InvalidCharacter('x'), 5));
+        assert_eq!(error("1 +"), (ParseError::UnexpectedEnd, 4));
+        assert_eq!(error("1 2"), (ParseError::UnexpectedToken, 3));
+        assert_eq!(error("1 * * 2"), (ParseError::UnexpectedToken, 5));
+        assert_eq!(error("1\n(1 + 2"), (ParseError::UnmatchedParen, 1));
+        assert_eq!(error("(1 + 2))"), (ParseError::UnmatchedParen, 8));
+        assert_eq!(error("()"), (ParseError::UnexpectedToken, 2));
+        assert_eq!(
+            error("1 + 9223372036854775808"),
+            (ParseError::IntegerTooLarge, 5)
+        );
+    }
+
+    #[test]
+    fn operators() {
+        let standard = &OpTable::STANDARD;
+        assert_eq!(eval("8 - 3 - 2", standard), Ok(3));
+        assert_eq!(eval("2 ^ 3 ^ 2", standard), Ok(512));
+        assert_eq!(eval("2 ^ 3 ^ 2", &OpTable::PART_1), Ok(64));
+        assert_eq!(eval("1 + 2 * 3 ^ 2", standard), Ok(19));
+        assert_eq!(eval("-2 ^ 2", standard), Ok(-4));
+        assert_eq!(eval("(-2) ^ 2", standard), Ok(4));
+        assert_eq!(eval("2 * -3 + 1", standard), Ok(-5));
+        assert_eq!(eval("--3", standard), Ok(3));
+        assert_eq!(eval("-3 + 4", &OpTable::PART_1), Ok(1));
+        assert_eq!(eval("7 / 2", standard), Ok(3));
+        assert_eq!(eval("-7 / 2", standard), Ok(-3));
+        assert_eq!(eval("1 / (2 - 2)", standard), Err(Error::DivisionByZero));
+        assert_eq!(eval("2 ^ -1", standard), Err(Error::NegativeExponent));
+        assert_eq!(eval("2 - 3 * 4", &OpTable::PART_2), Ok(-4));
+    }
+
+    #[test]
+    fn parse_line_errors() {
+        let input = "1 + 2\n(3 * 4";
+        let ctx = Context::new(input);
+        let line = input.lines().nth(1).unwrap();
+        let e = parse_line(&ctx, line, &OpTable::PART_2).unwrap_err();
+        let location = e.location.unwrap();
+        assert_eq!(e.error, ParseError::UnmatchedParen);
+        assert_eq!((location.line, location.col, location.len), (2, 1, 1));
+        let e = parse_line(&ctx, line, &OpTable::empty()).unwrap_err();
+        assert_eq!(e.error, ParseError::UnsupportedOperator(Mul));
+        assert_eq!(e.location.unwrap().col, 4);
+    }
+
+    #[test]
+    fn long_lines() {
+        // Neither long nor deeply nested lines may overflow the stack
+        const TERMS: usize = 100_000;
+        let sum = vec!["1"; TERMS].join(" + ");
+        let exprs = parse_input(&sum).unwrap();
+        assert_eq!(part_1(&exprs), Ok(Value::from(TERMS as i64)));
+        assert_eq!(part_2(&exprs), Ok(Value::from(TERMS as i64)));
+
+        let nested = format!("{}1{}", "(1 + ".repeat(TERMS), ")".repeat(TERMS));
+        let expr = parse(&nested, &OpTable::STANDARD);
+        assert_eq!(expr.evaluate(), Ok(TERMS as i64 + 1));
+        // Only the outermost parentheses are redundant
+        assert!(expr.pretty(&OpTable::STANDARD) == nested[1..nested.len() - 1]);
+        assert!(parse(&expr.to_string(), &OpTable::PART_1) == expr);
+        assert_eq!(expr.postfix().len(), 4 * TERMS + 1);
+
+        let parens = format!("{}2{}", "(".repeat(TERMS), ")".repeat(TERMS));
+        assert_eq!(eval(&parens, &OpTable::PART_2), Ok(2));
+        let powers = vec!["1"; TERMS].join(" ^ ");
+        assert_eq!(eval(&powers, &OpTable::STANDARD), Ok(1));
+        let negations = format!("{}3", "-".repeat(TERMS));
+        assert_eq!(eval(&negations, &OpTable::STANDARD), Ok(3));
+    }
+
+    #[test]
+    fn overflow() {
+        let standard = &OpTable::STANDARD;
+        assert_eq!(eval("9223372036854775807", standard), Ok(i64::MAX));
+        assert_eq!(
+            eval("1 + 3037000500 * 3037000500", standard),
+            Err(Error::Overflow("(3037000500 * 3037000500)".to_string()))
+        );
+        assert_eq!(
+            eval("-(-9223372036854775807 - 1)", standard),
+            Err(Error::Overflow("-(-9223372036854775807 - 1)".to_string()))
+        );
+        assert_eq!(
+            eval("2 ^ 63", standard),
+            Err(Error::Overflow("(2 ^ 63)".to_string()))
+        );
+        assert_eq!(eval("-2 ^ 62 * 2", standard), Ok(i64::MIN));
+        let exprs = parse_input("9223372036854775807\n1 * 1").unwrap();
+        assert_eq!(
+            sum::<i64>(&exprs, &OpTable::PART_1),
+            Err(Error::Overflow("9223372036854775807 + (1 * 1)".to_string()))
+        );
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn bigint() {
+        let exprs = parse_input("3037000500 * 3037000500 + 1\n2 ^ 64").unwrap();
+        let value = |s: &str| s.parse::<Value>().unwrap();
+        assert_eq!(part_1(&exprs), Ok(value("27670116110709801617")));
+        assert_eq!(part_2(&exprs), Ok(value("27670116113746802116")));
+        assert_eq!(
+            parse("-(-9223372036854775807 - 1)", &OpTable::STANDARD).evaluate(),
+            Ok(value("9223372036854775808"))
+        );
+    }
+
+    #[test]
+    fn custom_table() {
+        let tokens = &parse_input("2 * 3 + 4 - 1").unwrap()[0];
+        let table = OpTable::empty().binary(&[Add, Mul], 1, Right);
+        let expr = Expr::parse(tokens, &table);
+        assert_eq!(
+            expr,
+            Err(SyntaxError {
+                error: ParseError::UnsupportedOperator(Sub),
+                token: 5
+            })
+        );
+        let table = table.binary(&[Sub], 1, Right);
+        assert_eq!(Expr::parse(tokens, &table).unwrap().evaluate(), Ok(12i64));
+        assert_eq!(
+            Expr::parse(&parse_input("-1").unwrap()[0], &table),
+            Err(SyntaxError {
+                error: ParseError::UnsupportedOperator(Sub),
+                token: 0
+            })
+        );
+        let table = table.negation(0);
+        assert_eq!(eval("-1 * 2", &table), Ok(-2));
+        assert_eq!(
+            part_1(&parse_input("1 / 0").unwrap()),
+            Err(Error::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn print() {
+        let expr = parse("(1 + 2) * 3 - -4 ^ 2", &OpTable::STANDARD);
+        assert_eq!(expr.to_string(), "(((1 + 2) * 3) - -(4 ^ 2))");
+        assert_eq!(expr.pretty(&OpTable::STANDARD), "(1 + 2) * 3 - -4 ^ 2");
+        assert_eq!(expr.pretty(&OpTable::PART_1), "1 + 2 * 3 - -(4 ^ 2)");
+        assert_eq!(expr.pretty(&OpTable::PART_2), "(1 + 2 * 3) - -4 ^ 2");
+        assert_eq!(
+            expr.dump(),
+            "\
+Sub
+  Mul
+    Add
+      1
+      2
+    3
+  Neg
+    Pow
+      4
+      2"
+        );
+        assert_eq!(expr.postfix(), "1 2 + 3 * 4 2 ^ neg -");
+        let expr = parse("1 + 2 * 3", &OpTable::PART_1);
+        assert_eq!(expr.pretty(&OpTable::PART_1), "1 + 2 * 3");
+        assert_eq!(expr.postfix(), "1 2 + 3 *");
+        assert_eq!(expr.pretty(&OpTable::STANDARD), "(1 + 2) * 3");
+        let expr = parse("2 ^ 3 ^ 2", &OpTable::STANDARD);
+        assert_eq!(expr.pretty(&OpTable::STANDARD), "2 ^ 3 ^ 2");
+        assert_eq!(expr.pretty(&OpTable::PART_1), "2 ^ (3 ^ 2)");
+    }
+
+    #[test]
+    fn pretty_round_trip() {
+        let tables = [OpTable::PART_1, OpTable::PART_2, OpTable::STANDARD];
+        let inputs = EXAMPLE_INPUTS
+            .iter()
+            .chain(&["-(1 - 2) ^ -(3 / 4) - 5", "2 ^ -3 ^ (2 - 1) * -(-4)"]);
+        for input in inputs {
+            for from in &tables {
+                let expr = parse(input, from);
+                for to in &tables {
+                    assert_eq!(parse(&expr.pretty(to), to), expr, "{}", input);
+                }
+            }
+        }
     }
 }
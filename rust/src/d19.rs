@@ -1,16 +1,21 @@
-use crate::dbg_eprintln;
+use crate::parse::{Context, Located, ResultExt};
+use crate::solution::Solution;
 use crate::str::split_once;
 use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::iter;
+use std::ops::RangeInclusive;
 use Rule::*;
 
 #[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone)]
-enum Rule<'a> {
+pub enum Rule<'a> {
     Literal(&'a str),
     Composite(Vec<Vec<usize>>),
 }
 
-// Inedexed by rule Id
-type Rules<'a> = Vec<Rule<'a>>;
+/// Rules keyed by Id, which need not be contiguous (or small)
+pub type Rules<'a> = BTreeMap<usize, Rule<'a>>;
 
 /// Key properties:
 /// - Two "base" types:
@@ -56,125 +61,108 @@ type Rules<'a> = Vec<Rule<'a>>;
 ///     0 = "ababa" | "abaa" | "aaa" | "aaba"
 ///     0 = ^a(?:ba|a)(?:ba|a)$
 ///     ```
+///
+/// `count_matches` checks that the rules don't have any loops before compiling them.
 fn part_1(rules: &Rules, messages: &[&str]) -> usize {
-    let re_str = rules2regex(&rules);
-    dbg_eprintln!("{}", re_str);
-    let re = Regex::new(&re_str).expect("invalid regex compiled from rule"); // FIXME
-    messages.iter().filter(|s| re.is_match(s)).count()
+    count_matches(rules, messages)
 }
 
-/// Observation: Rules 8 and 11 *only* appear in rule `0: 8 11` (the target rule).
-/// - Matching messages must therefore start/end with matches for rules `8`/`11`
-///
-/// New rule `8: 42 | 42 8` is equivalent to one or more matches of rule `42` (`42+`)
-///
-/// New rule `11: 42 31 | 42 11 31` is equivalent to `42 (?:11|?R))* 31`
-/// - Recursive in between leading and trailing `42`/`31`
-///
-/// Rule `0 is now equivalent to `^42+ (?:42 (?:11|?R) 31)$`
+/// Key points:
+/// - Rules `8: 42 | 42 8` and `11: 42 31 | 42 11 31` introduce loops
+///     - The set of possible matches is now infinite -> can't be compiled to a regex
 ///
-/// Observation:
-/// - Message must start with at least one match for rule `42`
-/// - Message must end with at least one match for rule `31`
-///
-/// Observation: In a matching message, the number of matches for rule `31` in the *ending* section
-/// (e.g. `31+$`) plus `1` is the *minimum* number of matches for rule `42` in the *preceding* section.
-/// - Given the message starts/ends with matches for rules `42`/`31`
-/// - Rule `11` starts with a single match for rule `42`, followed by `n` recursive matches
-///   with 1 match for rule `42` and rule `31` each.
-///
-/// Match this using several Regexes:
-/// - Check start & end patterns: `^(?P<start>(?:42)+)(?P<end>(?:31)+)$`
-/// - Count number of rule `31` matches in `end` section: `(31)`
-/// - Count number of rule `42` matches in `start` section: `(42)`
-/// - Compare counts to determine validity
-///
-/// Note: As stated by the puzzle, this *only* applies to the rules/messages in the puzzle input
-/// and does not hold in the general case.
+/// Rather than special casing these rules, `count_matches` falls back to a general `Matcher`
+/// which supports any (recursive) rules.
 fn part_2(rules: &Rules, messages: &[&str]) -> usize {
-    // FIXME: Avoid this cloning?
     let mut rules = rules.clone();
-    rules[8] = Composite(vec![vec![42], vec![42, 8]]);
-    rules[11] = Composite(vec![vec![42, 31], vec![42, 11, 31]]);
-
-    let r31_pattern = rule2regex(&rules, &rules[31]);
-    let r42_pattern = rule2regex(&rules, &rules[42]);
-
-    let start_end_re = Regex::new(&format!(
-        "^(?P<start>(?:{})+)(?P<end>(?:{})+)$",
-        r42_pattern, r31_pattern
-    ))
-    .expect("invalid start/end pattern");
-
-    let r31_cap =
-        Regex::new(&format!("({})", r31_pattern)).expect("invalid rule 31 capture pattern");
-    let r42_cap =
-        Regex::new(&format!("({})", r42_pattern)).expect("invalid rule 42 capture pattern");
-
-    messages
-        .iter()
-        .filter(|m| {
-            let mut start_end_caps = start_end_re.captures_iter(m);
-            match (start_end_caps.next(), start_end_caps.next()) {
-                (Some(c), None) => {
-                    r42_cap.find_iter(&c["start"]).count() > r31_cap.find_iter(&c["end"]).count()
-                }
-                _ => false,
-            }
-        })
-        .count()
+    rules.insert(8, Composite(vec![vec![42], vec![42, 8]]));
+    rules.insert(11, Composite(vec![vec![42, 31], vec![42, 11, 31]]));
+    count_matches(&rules, messages)
 }
 
-pub fn run(input: &str) {
-    let (rules, messages) = parse_input(input).expect("unable to parse input");
-    println!("Part 1: {}", part_1(&rules, &messages));
-    println!("Part 2: {}", part_2(&rules, &messages));
+/// Number of `messages` which completely match rule `0`, using the `Strategy` chosen by
+/// analysing `rules`.
+fn count_matches(rules: &Rules, messages: &[&str]) -> usize {
+    match Analysis::new(rules).strategy() {
+        Strategy::Regex => {
+            let re_str = rules2regex(rules);
+            dbg_eprintln!("{}", re_str);
+            let re = Regex::new(&re_str).expect("invalid regex compiled from rule");
+            messages.iter().filter(|s| re.is_match(s)).count()
+        }
+        Strategy::General => {
+            let matcher = Matcher::new(rules);
+            messages.iter().filter(|m| matcher.is_match(m)).count()
+        }
+    }
 }
 
-fn parse_input(input: &str) -> Result<(Rules, Vec<&str>), ParseError> {
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Parsed = (Rules<'a>, Vec<&'a str>);
+    type Answer1 = usize;
+    type Answer2 = usize;
+    type ParseError = Located<ParseError>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1((rules, messages): &Self::Parsed) -> Self::Answer1 {
+        part_1(rules, messages)
+    }
+
+    fn part_2((rules, messages): &Self::Parsed) -> Self::Answer2 {
+        part_2(rules, messages)
+    }
+}
+
+fn parse_input(input: &str) -> Result<(Rules<'_>, Vec<&str>), Located<ParseError>> {
+    let ctx = Context::new(input);
     let mut lines = input.lines();
-    let mut rules = lines
-        .by_ref()
-        .take_while(|l| *l != "")
-        .map(|l| {
-            let (id, rest) = split_once(l, ": ").ok_or(ParseError::InvalidRule)?;
-            let id = parse_id(id)?;
-
-            let rule = if rest.starts_with('"') {
-                Literal(
-                    rest.strip_prefix("\"")
-                        .and_then(|s| s.strip_suffix("\""))
-                        .ok_or(ParseError::InvalidRule)?,
-                )
-            } else {
-                let alt_rules = rest
-                    .split(" | ")
-                    .map(|rule_seq| {
-                        rule_seq
-                            .split_ascii_whitespace()
-                            .map(|id| parse_id(id))
-                            .collect::<Result<_, _>>()
-                    })
-                    .collect::<Result<_, _>>()?;
-                Composite(alt_rules)
-            };
-            Ok((id, rule))
-        })
-        .collect::<Result<Vec<_>, _>>()?;
-    rules.sort_by(|(id_a, _), (id_b, _)| id_a.cmp(id_b));
-    let rules = rules.into_iter().map(|(_, r)| r).collect();
+    let mut rules = Rules::new();
+    for l in lines.by_ref().take_while(|l| !l.is_empty()) {
+        let (id_str, rest) = split_once(l, ": ")
+            .ok_or(ParseError::InvalidRule)
+            .at(&ctx, l)?;
+        let id = parse_id(id_str).at(&ctx, id_str)?;
+
+        let rule = if rest.starts_with('"') {
+            Literal(
+                rest.strip_prefix("\"")
+                    .and_then(|s| s.strip_suffix("\""))
+                    .ok_or(ParseError::InvalidRule)
+                    .at(&ctx, rest)?,
+            )
+        } else {
+            let alt_rules = rest
+                .split(" | ")
+                .map(|rule_seq| {
+                    rule_seq
+                        .split_ascii_whitespace()
+                        .map(|s| parse_id(s).at(&ctx, s))
+                        .collect::<Result<_, _>>()
+                })
+                .collect::<Result<_, _>>()?;
+            Composite(alt_rules)
+        };
+        if rules.insert(id, rule).is_some() {
+            return Err(ctx.error(id_str, ParseError::DuplicateRuleId(id)));
+        }
+    }
 
     Ok((rules, lines.collect()))
 }
 
 fn parse_id(s: &str) -> Result<usize, ParseError> {
-    s.parse::<usize>().map_err(|e| ParseError::InvalidRuleId(e))
+    s.parse::<usize>().map_err(ParseError::InvalidRuleId)
 }
 
 /// Compiles `rules[0]` into a single non-capturing Regex string to match an *entire* message.
-fn rules2regex<'a>(rules: &[Rule<'a>]) -> String {
+fn rules2regex<'a>(rules: &Rules<'a>) -> String {
     // Include anchors as a message must match *entirely*
-    return format!("^{}$", rule2regex(rules, &rules[0]));
+    format!("^{}$", rule2regex(rules, &rules[&0]))
 }
 
 /// Compiles `r` into a single non-capturing Regex string to match *part* of a message.
@@ -183,17 +171,17 @@ fn rules2regex<'a>(rules: &[Rule<'a>]) -> String {
 // TODO: Avoid recursion - this was (IMO) the easiest/natural implementation and works absolutely
 //       fine for the size of the puzzle input. However, it requires quite a lot of Vec allocation
 //       & String joining as well as potentially causing issue for larger rules/rule sets.
-fn rule2regex<'a>(rules: &[Rule<'a>], r: &'a Rule) -> String {
+fn rule2regex<'a>(rules: &Rules<'a>, r: &'a Rule) -> String {
     match r {
         // Base case: Literal rules cannot be further expanded
-        Literal(s) => s.to_string(),
+        Literal(s) => regex::escape(s),
         // Recursive case: Composite rules can be expanded into alternations between
         // groups of Literal rules
         Composite(alts) => {
             // Alternatives expanded into groups of literals
             let groups = alts
                 .iter()
-                .map(|seq| seq.iter().map(|id| rule2regex(rules, &rules[*id])));
+                .map(|seq| seq.iter().map(|id| rule2regex(rules, &rules[id])));
             if alts.len() > 1 {
                 // Multiple alternatives -> multiple alternate non-capturing groups
                 format!(
@@ -211,12 +199,640 @@ fn rule2regex<'a>(rules: &[Rule<'a>], r: &'a Rule) -> String {
     }
 }
 
+/// Matches messages against an arbitrary set of rules, including recursive rules (left, right
+/// or otherwise), rules with empty alternatives and references to rules which don't exist (which
+/// match nothing).
+///
+/// Uses an [Earley parser](https://en.wikipedia.org/wiki/Earley_parser): for each position in
+/// the message, track the set of partially matched alternatives (`Item`s) which could lead to a
+/// match of the target rule. Each set is built from the previous sets by:
+/// - Predicting: an item expecting composite rule `r` next adds the alternatives of `r`, starting
+///   at the current position
+/// - Scanning: an item expecting literal rule `r` next is advanced past `r` in the set at the end
+///   of the literal, if the message continues with it
+/// - Completing: a fully matched item for rule `r` advances the items which were expecting `r`
+///   at the position the item started
+///
+/// The message matches if the last set contains a fully matched alternative of the target rule
+/// which started at the beginning of the message. This takes `O(n^3)` time in the length of the
+/// message for any rule set (and `O(n)` for most).
+pub struct Matcher<'r, 'a> {
+    rules: &'r Rules<'a>,
+    /// Ids of the rules which can match the empty string
+    nullable: HashSet<usize>,
+}
+
+/// The sets of `Item`s at each position in a message.
+struct Chart {
+    sets: Vec<Vec<Item>>,
+    /// The same items as `sets`, for checking whether an item is in a set
+    seen: Vec<HashSet<Item>>,
+}
+
+impl Chart {
+    fn add(&mut self, pos: usize, item: Item) {
+        if self.seen[pos].insert(item) {
+            self.sets[pos].push(item);
+        }
+    }
+
+    fn contains(&self, pos: usize, item: Item) -> bool {
+        self.seen[pos].contains(&item)
+    }
+}
+
+/// An alternative of a composite rule, matched up to `dot` since position `start` in the message.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+struct Item {
+    rule: usize,
+    alt: usize,
+    dot: usize,
+    start: usize,
+}
+
+impl<'r, 'a> Matcher<'r, 'a> {
+    pub fn new(rules: &'r Rules<'a>) -> Self {
+        // Completing an empty match would have to advance items added to the same set *after*
+        // it has been completed. Instead, items are advanced past nullable rules when predicting
+        // them, which requires knowing which rules are nullable up front.
+        let mut nullable = HashSet::new();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (&id, rule) in rules {
+                let is_nullable = match rule {
+                    Literal(s) => s.is_empty(),
+                    Composite(alts) => alts
+                        .iter()
+                        .any(|seq| seq.iter().all(|r| nullable.contains(r))),
+                };
+                if is_nullable && nullable.insert(id) {
+                    changed = true;
+                }
+            }
+        }
+        Matcher { rules, nullable }
+    }
+
+    /// Whether `message` *completely* matches rule `0`.
+    pub fn is_match(&self, message: &str) -> bool {
+        self.matches_rule(0, message)
+    }
+
+    /// Whether `message` *completely* matches rule `rule`.
+    pub fn matches_rule(&self, rule: usize, message: &str) -> bool {
+        match self.rules.get(&rule) {
+            None => false,
+            Some(Literal(s)) => *s == message,
+            Some(Composite(alts)) => {
+                let chart = self.chart(rule, message);
+                (0..alts.len()).any(|alt| chart.contains(message.len(), self.complete(rule, alt, 0)))
+            }
+        }
+    }
+
+    /// How `message` matches rule `rule`: which alternative of each rule matched which part of
+    /// the message. If the message doesn't match, the furthest position in the message a match
+    /// could reach and the literals which would have continued it.
+    pub fn explain<'m>(&self, rule: usize, message: &'m str) -> Result<Derivation<'m>, Failure<'a>> {
+        let n = message.len();
+        match self.rules.get(&rule) {
+            None => Err(Failure {
+                position: 0,
+                expected: vec![],
+            }),
+            Some(Literal(s)) if message.starts_with(s) => {
+                if s.len() == n {
+                    Ok(Derivation {
+                        rule,
+                        alt: None,
+                        text: message,
+                        start: 0,
+                        children: vec![],
+                    })
+                } else {
+                    Err(Failure {
+                        position: s.len(),
+                        expected: vec![],
+                    })
+                }
+            }
+            Some(Literal(s)) => Err(Failure {
+                position: 0,
+                expected: vec![(rule, s)],
+            }),
+            Some(Composite(_)) => {
+                let chart = self.chart(rule, message);
+                if let Some(derivation) = self.derive(&chart, message, rule, 0, n, &mut vec![]) {
+                    return Ok(derivation);
+                }
+                let position = (0..=n).rev().find(|&p| !chart.sets[p].is_empty()).unwrap_or(0);
+                let expected = chart.sets[position]
+                    .iter()
+                    .filter_map(|&item| {
+                        let id = self.next(item)?;
+                        match self.rules.get(&id)? {
+                            Literal(s) if !s.is_empty() => Some((id, *s)),
+                            _ => None,
+                        }
+                    })
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .collect();
+                Err(Failure { position, expected })
+            }
+        }
+    }
+
+    /// The Earley sets of `message` for matching the composite rule `rule`.
+    fn chart(&self, rule: usize, message: &str) -> Chart {
+        let n = message.len();
+        let mut chart = Chart {
+            sets: vec![vec![]; n + 1],
+            seen: vec![HashSet::new(); n + 1],
+        };
+        if let Some(Composite(alts)) = self.rules.get(&rule) {
+            for alt in 0..alts.len() {
+                let item = Item {
+                    rule,
+                    alt,
+                    dot: 0,
+                    start: 0,
+                };
+                chart.add(0, item);
+            }
+        }
+        for pos in 0..=n {
+            let mut i = 0;
+            while i < chart.sets[pos].len() {
+                let item = chart.sets[pos][i];
+                i += 1;
+                let advanced = Item {
+                    dot: item.dot + 1,
+                    ..item
+                };
+                match self.next(item).map(|id| (id, self.rules.get(&id))) {
+                    // Complete
+                    None => {
+                        for j in 0..chart.sets[item.start].len() {
+                            let waiting = chart.sets[item.start][j];
+                            if self.next(waiting) == Some(item.rule) {
+                                let waiting = Item {
+                                    dot: waiting.dot + 1,
+                                    ..waiting
+                                };
+                                chart.add(pos, waiting);
+                            }
+                        }
+                    }
+                    // Scan
+                    Some((_, Some(Literal(s)))) => {
+                        if message[pos..].starts_with(s) {
+                            chart.add(pos + s.len(), advanced);
+                        }
+                    }
+                    // Predict
+                    Some((id, Some(Composite(alts)))) => {
+                        for alt in 0..alts.len() {
+                            let predicted = Item {
+                                rule: id,
+                                alt,
+                                dot: 0,
+                                start: pos,
+                            };
+                            chart.add(pos, predicted);
+                        }
+                        if self.nullable.contains(&id) {
+                            chart.add(pos, advanced);
+                        }
+                    }
+                    // Rule doesn't exist -> the item can't be matched any further
+                    Some((_, None)) => {}
+                }
+            }
+        }
+        chart
+    }
+
+    /// A derivation of `message[start..end]` from `rule`, found by working backwards through
+    /// `chart`. `visiting` is the rules and spans being derived by the callers, which are skipped
+    /// to avoid looping forever on rules which can match the same span through a cycle.
+    fn derive<'m>(
+        &self,
+        chart: &Chart,
+        message: &'m str,
+        rule: usize,
+        start: usize,
+        end: usize,
+        visiting: &mut Vec<(usize, usize, usize)>,
+    ) -> Option<Derivation<'m>> {
+        let alts = match self.rules.get(&rule)? {
+            Literal(s) => {
+                return (message[start..end] == **s).then(|| Derivation {
+                    rule,
+                    alt: None,
+                    text: &message[start..end],
+                    start,
+                    children: vec![],
+                })
+            }
+            Composite(alts) => alts,
+        };
+        if visiting.contains(&(rule, start, end)) {
+            return None;
+        }
+        visiting.push((rule, start, end));
+        let derivation = (0..alts.len())
+            .filter(|&alt| chart.contains(end, self.complete(rule, alt, start)))
+            .find_map(|alt| {
+                let item = self.complete(rule, alt, start);
+                let children = self.derive_seq(chart, message, item, end, visiting)?;
+                Some(Derivation {
+                    rule,
+                    alt: Some(alt),
+                    text: &message[start..end],
+                    start,
+                    children,
+                })
+            });
+        visiting.pop();
+        derivation
+    }
+
+    /// Derivations of the rules before the dot of `item`, which together match
+    /// `message[item.start..end]`.
+    fn derive_seq<'m>(
+        &self,
+        chart: &Chart,
+        message: &'m str,
+        item: Item,
+        end: usize,
+        visiting: &mut Vec<(usize, usize, usize)>,
+    ) -> Option<Vec<Derivation<'m>>> {
+        if item.dot == 0 {
+            return (item.start == end).then(Vec::new);
+        }
+        let prev = Item {
+            dot: item.dot - 1,
+            ..item
+        };
+        let rule = self.next(prev)?;
+        // The last rule matches from some position at which `prev` had been matched
+        (item.start..=end)
+            .rev()
+            .filter(|&mid| chart.contains(mid, prev))
+            .find_map(|mid| {
+                let last = self.derive(chart, message, rule, mid, end, visiting)?;
+                let mut children = self.derive_seq(chart, message, prev, mid, visiting)?;
+                children.push(last);
+                Some(children)
+            })
+    }
+
+    /// Alternative `alt` of composite rule `rule` completely matched since `start`.
+    fn complete(&self, rule: usize, alt: usize, start: usize) -> Item {
+        let dot = match &self.rules[&rule] {
+            Composite(alts) => alts[alt].len(),
+            Literal(_) => 0,
+        };
+        Item {
+            rule,
+            alt,
+            dot,
+            start,
+        }
+    }
+
+    /// Id of the rule `item` expects to match next, or `None` if it has been completely matched.
+    fn next(&self, item: Item) -> Option<usize> {
+        match &self.rules[&item.rule] {
+            Composite(alts) => alts[item.alt].get(item.dot).copied(),
+            Literal(_) => None,
+        }
+    }
+}
+
+/// How (part of) a message matches a rule.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Derivation<'m> {
+    pub rule: usize,
+    /// Index of the alternative of a composite rule which matched, `None` for literal rules
+    pub alt: Option<usize>,
+    /// The part of the message matched
+    pub text: &'m str,
+    /// Position of `text` in the message
+    pub start: usize,
+    /// Derivations of the rules of the alternative, in order
+    pub children: Vec<Derivation<'m>>,
+}
+
+/// A tree with a line for each rule, its alternative and the text it matched e.g.
+/// ```text
+/// 0: 4 1 5 "ababbb"
+/// ├── 4: "a"
+/// ├── 1: 3 2 "babb"
+/// │   ├── 3: 5 4 "ba"
+/// ...
+/// ```
+impl fmt::Display for Derivation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_tree(f, "", "")
+    }
+}
+
+impl Derivation<'_> {
+    /// Write this node after `first` and its children after `indent`.
+    fn write_tree(&self, f: &mut fmt::Formatter<'_>, first: &str, indent: &str) -> fmt::Result {
+        write!(f, "{}{}:", first, self.rule)?;
+        for child in &self.children {
+            write!(f, " {}", child.rule)?;
+        }
+        write!(f, " {:?}", self.text)?;
+        for (i, child) in self.children.iter().enumerate() {
+            writeln!(f)?;
+            let (first, rest) = if i + 1 == self.children.len() {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            child.write_tree(f, &format!("{}{}", indent, first), &format!("{}{}", indent, rest))?;
+        }
+        Ok(())
+    }
+}
+
+/// Why a message doesn't match a rule.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Failure<'a> {
+    /// Furthest position in the message which a match of the rule could reach
+    pub position: usize,
+    /// Ids and strings of the literal rules which could have continued the match at `position`,
+    /// sorted by Id. Empty if the match could only end at `position`.
+    pub expected: Vec<(usize, &'a str)>,
+}
+
+impl fmt::Display for Failure<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no match beyond position {}: expected ", self.position)?;
+        if self.expected.is_empty() {
+            return write!(f, "end of message");
+        }
+        let expected: Vec<String> = self
+            .expected
+            .iter()
+            .map(|(id, s)| format!("{} ({:?})", id, s))
+            .collect();
+        write!(f, "{}", expected.join(" or "))
+    }
+}
+
+/// How messages are matched against a set of rules.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Strategy {
+    /// Compile the rules into a single regex (see `part_1`)
+    Regex,
+    /// Use a `Matcher`
+    General,
+}
+
+/// Properties of a set of rules, for deciding how to match messages against them.
+///
+/// Rule `r` *refers* to rule `s` if `s` appears in one of the alternatives of `r`, and *reaches*
+/// `s` if there is a chain of references from `r` to `s`.
+pub struct Analysis<'r, 'a> {
+    rules: &'r Rules<'a>,
+    /// Groups of rules which reach each other, sorted by Id. A rule which refers to itself
+    /// forms a group on its own
+    pub cycles: Vec<Vec<usize>>,
+    /// Ids which are referred to but don't have a rule, sorted
+    pub undefined: Vec<usize>,
+    /// Ids of rules which are not reached from rule `0`, sorted
+    pub unreachable: Vec<usize>,
+    /// Whether rule `0` is known to match a regular language (see `Analysis::new`)
+    pub regular: bool,
+    /// Rules which reach themselves or a rule which does
+    recursive: BTreeSet<usize>,
+    /// Shortest and longest matches of non-recursive rules which match anything
+    lengths: BTreeMap<usize, (usize, usize)>,
+    /// Whether rule `0` is, or reaches, an undefined Id
+    reaches_undefined: bool,
+}
+
+impl<'r, 'a> Analysis<'r, 'a> {
+    /// Analyse `rules`.
+    ///
+    /// Deciding whether a rule set matches a regular language is impossible in general, so this
+    /// only recognises the common cases: rules without cycles (which match a finite set of
+    /// strings) and cycles which only grow at one end. For example `8: 42 | 42 8` is equivalent
+    /// to the regex `(?:42)+` but `11: 42 31 | 42 11 31` (which matches `n` matches of `42`
+    /// followed by `n` matches of `31`) is not regular.
+    pub fn new(rules: &'r Rules<'a>) -> Self {
+        let refers = |id: usize| -> Vec<usize> {
+            match rules.get(&id) {
+                Some(Composite(alts)) => alts.iter().flatten().copied().collect(),
+                _ => vec![],
+            }
+        };
+
+        // Rules reached by each rule, found by a depth first search from every rule
+        let reaches: BTreeMap<usize, BTreeSet<usize>> = rules
+            .keys()
+            .map(|&id| {
+                let mut reached = BTreeSet::new();
+                let mut stack = refers(id);
+                while let Some(r) = stack.pop() {
+                    if reached.insert(r) {
+                        stack.extend(refers(r));
+                    }
+                }
+                (id, reached)
+            })
+            .collect();
+        let reaches_rule = |from: usize, to: usize| reaches.get(&from).is_some_and(|r| r.contains(&to));
+
+        let cycles: Vec<Vec<usize>> = rules
+            .keys()
+            .filter(|&&id| reaches_rule(id, id))
+            .map(|&id| {
+                rules
+                    .keys()
+                    .copied()
+                    .filter(|&r| reaches_rule(id, r) && reaches_rule(r, id))
+                    .collect::<Vec<_>>()
+            })
+            // Each cycle is found once for each rule in it
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let undefined: Vec<usize> = rules
+            .keys()
+            .flat_map(|&id| refers(id))
+            .filter(|r| !rules.contains_key(r))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let reached_from_0 = |id: usize| id == 0 || reaches_rule(0, id);
+        let reaches_undefined = !rules.contains_key(&0)
+            || reaches[&0].iter().any(|r| !rules.contains_key(r));
+        let unreachable = rules
+            .keys()
+            .copied()
+            .filter(|&id| !reached_from_0(id))
+            .collect();
+        let recursive: BTreeSet<usize> = rules
+            .keys()
+            .copied()
+            .filter(|&id| {
+                reaches_rule(id, id) || reaches[&id].iter().any(|&r| reaches_rule(r, r))
+            })
+            .collect();
+        let regular = cycles
+            .iter()
+            .filter(|cycle| reached_from_0(cycle[0]))
+            .all(|cycle| is_linear(rules, cycle));
+
+        let mut memo = HashMap::new();
+        let lengths = rules
+            .keys()
+            .filter(|id| !recursive.contains(id))
+            .filter_map(|&id| Some((id, match_lengths(rules, id, &mut memo)?)))
+            .collect();
+
+        Analysis {
+            rules,
+            cycles,
+            undefined,
+            unreachable,
+            regular,
+            recursive,
+            lengths,
+            reaches_undefined,
+        }
+    }
+
+    /// Whether `rule` reaches itself, or another rule which does, and so may match infinitely
+    /// many strings.
+    pub fn is_recursive(&self, rule: usize) -> bool {
+        self.recursive.contains(&rule)
+    }
+
+    /// Range of the lengths of the strings matched by `rule`, or `None` if the rule is recursive
+    /// or matches nothing.
+    pub fn lengths(&self, rule: usize) -> Option<RangeInclusive<usize>> {
+        let &(min, max) = self.lengths.get(&rule)?;
+        Some(min..=max)
+    }
+
+    /// Every string matched by `rule`, or `None` if the rule is recursive or matches more than
+    /// `limit` strings.
+    pub fn strings(&self, rule: usize, limit: usize) -> Option<BTreeSet<String>> {
+        if self.is_recursive(rule) {
+            return None;
+        }
+        let alts = match self.rules.get(&rule) {
+            None => return Some(BTreeSet::new()),
+            Some(Literal(s)) => return Some(iter::once(s.to_string()).collect()),
+            Some(Composite(alts)) => alts,
+        };
+        let mut strings = BTreeSet::new();
+        for seq in alts {
+            let mut prefixes: BTreeSet<String> = iter::once(String::new()).collect();
+            for &r in seq {
+                let suffixes = self.strings(r, limit)?;
+                prefixes = prefixes
+                    .iter()
+                    .flat_map(|p| suffixes.iter().map(move |s| format!("{}{}", p, s)))
+                    .collect();
+                if prefixes.len() > limit {
+                    return None;
+                }
+            }
+            strings.extend(prefixes);
+            if strings.len() > limit {
+                return None;
+            }
+        }
+        Some(strings)
+    }
+
+    /// `Strategy::Regex` if rule `0` matches a finite set of strings (the only case `rules2regex`
+    /// supports), otherwise `Strategy::General`.
+    pub fn strategy(&self) -> Strategy {
+        if !self.is_recursive(0) && !self.reaches_undefined {
+            Strategy::Regex
+        } else {
+            Strategy::General
+        }
+    }
+}
+
+/// Whether every alternative of the rules in `cycle` refers to at most one rule in the cycle,
+/// which is always the last rule of the alternative (or always the first). Such rules only ever
+/// grow a match at one end, like the regex `(?:ab)+`.
+fn is_linear(rules: &Rules, cycle: &[usize]) -> bool {
+    let alts = cycle.iter().flat_map(|id| match &rules[id] {
+        Literal(_) => &[][..],
+        Composite(alts) => &alts[..],
+    });
+    let (mut left, mut right) = (true, true);
+    for seq in alts {
+        let positions: Vec<usize> = seq
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| cycle.contains(r))
+            .map(|(i, _)| i)
+            .collect();
+        match positions[..] {
+            [] => {}
+            [i] => {
+                left &= i == 0;
+                right &= i == seq.len() - 1;
+            }
+            _ => return false,
+        }
+    }
+    left || right
+}
+
+/// Shortest and longest matches of the non-recursive rule `id` (`None` if it matches nothing),
+/// memoised in `memo`.
+fn match_lengths(
+    rules: &Rules,
+    id: usize,
+    memo: &mut HashMap<usize, Option<(usize, usize)>>,
+) -> Option<(usize, usize)> {
+    if let Some(&l) = memo.get(&id) {
+        return l;
+    }
+    let result = match rules.get(&id)? {
+        Literal(s) => Some((s.len(), s.len())),
+        Composite(alts) => alts
+            .iter()
+            .filter_map(|seq| {
+                seq.iter().try_fold((0, 0), |(min, max), &r| {
+                    let (r_min, r_max) = match_lengths(rules, r, memo)?;
+                    Some((min + r_min, max + r_max))
+                })
+            })
+            .fold(None, |acc, (min, max)| match acc {
+                None => Some((min, max)),
+                Some((a, b)) => Some((a.min(min), b.max(max))),
+            }),
+    };
+    memo.insert(id, result);
+    result
+}
+
 #[derive(PartialEq, Eq, Debug)]
-enum ParseError {
+pub enum ParseError {
     InvalidRule,
     InvalidRuleId(std::num::ParseIntError),
+    /// More than one rule with the same Id
+    DuplicateRuleId(usize),
 }
 
+impl_error!(parse ParseError);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -255,14 +871,6 @@ aaabbb
 aaaabbb"#
     };
 
-    /// The example is missing some rules to make a contiguous set from `0..=41`. `parse_input`
-    /// assumes a full set as it sorts by `id` then collects into a `Vec`. Without a full set,
-    /// the solution functions will cause index out of bounds errors when looking up rule IDs.
-    /// Forunately, the example rules don't *actually* use any of the missing ones so I have filled
-    /// them in below with `Literal("x")` rules. These should never actually be matched against and
-    /// if they are it will be clear as no messages will ever match them.
-    /// This was quicker and easier than changing the implementation to accomodate for this (e.g.
-    /// using a HashMap keyed by rule id).
     #[rustfmt::skip]
     const EXAMPLE_INPUT_2: &str = {
 r#"42: 9 14 | 10 1
@@ -296,18 +904,6 @@ r#"42: 9 14 | 10 1
 18: 15 15
 7: 14 5 | 1 21
 24: 14 1
-32: "z"
-33: "z"
-34: "z"
-35: "z"
-36: "z"
-37: "z"
-38: "z"
-39: "z"
-40: "z"
-41: "z"
-29: "z"
-30: "z"
 
 abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa
 bbabbbbaabaabba
@@ -332,14 +928,14 @@ aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba"#
             let (rules, messages) = parse_input(input).unwrap();
             assert_eq!(
                 rules,
-                vec![
+                numbered(vec![
                     Composite(vec![vec![4, 1, 5]]),
                     Composite(vec![vec![2, 3], vec![3, 2]]),
                     Composite(vec![vec![4, 4], vec![5, 5]]),
                     Composite(vec![vec![4, 5], vec![5, 4]]),
                     Literal("a"),
                     Literal("b"),
-                ]
+                ])
             );
 
             assert_eq!(
@@ -354,14 +950,14 @@ aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba"#
         let (rules, messages) = parse_input(EXAMPLE_INPUT).unwrap();
         assert_eq!(
             rules,
-            vec![
+            numbered(vec![
                 Composite(vec![vec![4, 1, 5]]),
                 Composite(vec![vec![2, 3], vec![3, 2]]),
                 Composite(vec![vec![4, 4], vec![5, 5]]),
                 Composite(vec![vec![4, 5], vec![5, 4]]),
                 Literal("a"),
                 Literal("b"),
-            ]
+            ])
         );
 
         assert_eq!(
@@ -370,15 +966,155 @@ aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba"#
         );
     }
 
+    /// Rules with Ids `0..rules.len()`.
+    fn numbered<'a>(rules: Vec<Rule<'a>>) -> Rules<'a> {
+        rules.into_iter().enumerate().collect()
+    }
+
+    #[test]
+    fn parse_input_ids() {
+        let (rules, messages) = parse_input("4000000000: \"a\"\n0: 4000000000 1\n\naa").unwrap();
+        assert_eq!(
+            rules,
+            vec![
+                (0, Composite(vec![vec![4000000000, 1]])),
+                (4000000000, Literal("a"))
+            ]
+            .into_iter()
+            .collect()
+        );
+        assert_eq!(Analysis::new(&rules).undefined, [1]);
+        assert_eq!(part_1(&rules, &messages), 0);
+
+        let e = parse_input("0: 1\n1: \"a\"\n0: \"b\"\n\na").unwrap_err();
+        assert_eq!(e.error, ParseError::DuplicateRuleId(0));
+        let location = e.location.unwrap();
+        assert_eq!((location.line, location.col, location.len), (3, 1, 1));
+    }
+
+    #[test]
+    fn analyse_example() {
+        let (rules, _) = parse_input(EXAMPLE_INPUT).unwrap();
+        let analysis = Analysis::new(&rules);
+        assert!(analysis.cycles.is_empty());
+        assert!(analysis.undefined.is_empty());
+        assert!(analysis.unreachable.is_empty());
+        assert!(analysis.regular);
+        assert_eq!(analysis.strategy(), Strategy::Regex);
+        assert_eq!(analysis.lengths(0), Some(6..=6));
+        assert_eq!(analysis.lengths(3), Some(2..=2));
+        let strings = |rule, limit| {
+            analysis
+                .strings(rule, limit)
+                .map(|s| s.into_iter().collect::<Vec<_>>())
+        };
+        assert_eq!(strings(2, 2), Some(vec!["aa".to_string(), "bb".to_string()]));
+        assert_eq!(strings(0, 8).map(|s| s.len()), Some(8));
+        assert_eq!(strings(0, 7), None);
+    }
+
+    #[test]
+    fn analyse_part_2() {
+        let (mut rules, _) = parse_input(EXAMPLE_INPUT_2).unwrap();
+        let analysis = Analysis::new(&rules);
+        // Ids 29, 30 and 32..=41 have no rule, but nothing refers to them
+        assert_eq!(rules.len(), 31);
+        assert!(!rules.contains_key(&29) && !rules.contains_key(&41));
+        assert!(analysis.undefined.is_empty());
+        assert!(analysis.unreachable.is_empty());
+        assert_eq!(analysis.strategy(), Strategy::Regex);
+        assert_eq!(analysis.lengths(0), Some(15..=15));
+
+        rules.insert(8, Composite(vec![vec![42], vec![42, 8]]));
+        let analysis = Analysis::new(&rules);
+        assert_eq!(analysis.cycles, [vec![8]]);
+        assert!(analysis.regular);
+        assert!(analysis.is_recursive(0));
+        assert!(!analysis.is_recursive(42));
+        assert_eq!(analysis.lengths(8), None);
+        assert_eq!(analysis.lengths(42), Some(5..=5));
+        assert_eq!(analysis.strings(0, usize::MAX), None);
+        assert_eq!(analysis.strategy(), Strategy::General);
+
+        rules.insert(11, Composite(vec![vec![42, 31], vec![42, 11, 31]]));
+        let analysis = Analysis::new(&rules);
+        assert_eq!(analysis.cycles, [vec![8], vec![11]]);
+        assert!(!analysis.regular);
+    }
+
+    #[test]
+    fn analyse_invalid() {
+        let rules = vec![
+            (0, Composite(vec![vec![1, 4], vec![1]])),
+            (1, Composite(vec![vec![5, 2], vec![5]])),
+            (2, Composite(vec![vec![1, 5]])),
+            (3, Literal("x")),
+            (5, Literal("a")),
+        ]
+        .into_iter()
+        .collect();
+        let analysis = Analysis::new(&rules);
+        assert_eq!(analysis.cycles, [vec![1, 2]]);
+        assert_eq!(analysis.undefined, [4]);
+        assert_eq!(analysis.unreachable, [3]);
+        // `1` is `a(?:1a)?` - it grows at both ends
+        assert!(!analysis.regular);
+        assert_eq!(analysis.strategy(), Strategy::General);
+        assert_eq!(analysis.lengths(4), None);
+
+        let rules = numbered(vec![
+            Composite(vec![vec![1], vec![2, 7]]),
+            Literal("a"),
+            Literal("b"),
+        ]);
+        let analysis = Analysis::new(&rules);
+        assert_eq!(analysis.undefined, [7]);
+        assert!(analysis.regular);
+        assert_eq!(analysis.lengths(0), Some(1..=1));
+        assert_eq!(analysis.strings(0, 1), Some(iter::once("a".to_string()).collect()));
+        assert_eq!(analysis.strategy(), Strategy::General);
+        assert_eq!(count_matches(&rules, &["a", "b", "ab"]), 1);
+
+        let rules = vec![(1, Literal("a"))].into_iter().collect();
+        assert_eq!(Analysis::new(&rules).strategy(), Strategy::General);
+        assert_eq!(Analysis::new(&Rules::new()).strategy(), Strategy::General);
+        assert_eq!(count_matches(&rules, &["", "a"]), 0);
+    }
+
     #[test]
     fn rules2regex_simple() {
-        let rules = [
+        let rules = numbered(vec![
             Composite(vec![vec![1, 2, 2]]),
             Literal("a"),
             Composite(vec![vec![3, 1], vec![1]]),
             Literal("b"),
-        ];
+        ]);
         assert_eq!(rules2regex(&rules), "^a(?:ba|a)(?:ba|a)$");
+
+        let rules = numbered(vec![Composite(vec![vec![1, 1], vec![2]]), Literal("."), Literal("a|b*")]);
+        assert_eq!(rules2regex(&rules), r"^(?:\.\.|a\|b\*)$");
+    }
+
+    #[test]
+    fn strategies_agree() {
+        let inputs = [
+            EXAMPLE_INPUT,
+            "0: 1 1\n1: \".\"\n\n..\nab\n.\n...",
+            "0: 1 2 | 2\n1: \"a+\"\n2: \"(b)\"\n\na+(b)\naab\n(b)\nb\na+",
+            "0: 1 | 2 3\n1: \"^$\"\n2: \"[a-z]*\"\n3: \"\\d?\"\n\n^$\n\n[a-z]*\\d?\nabc1\n[a-z]*",
+            "0: 1 2\n1: \"\"\n2: \"{2}\"\n\n{2}\n\n{",
+        ];
+        for input in &inputs {
+            let (rules, messages) = parse_input(input).unwrap();
+            let re = Regex::new(&rules2regex(&rules)).unwrap();
+            let matcher = Matcher::new(&rules);
+            for m in &messages {
+                assert_eq!(re.is_match(m), matcher.is_match(m), "{:?} in {:?}", m, input);
+            }
+            assert_eq!(Analysis::new(&rules).strategy(), Strategy::Regex);
+        }
+        let (rules, messages) = parse_input(inputs[1]).unwrap();
+        assert_eq!(count_matches(&rules, &messages), 1);
     }
 
     #[test]
@@ -387,9 +1123,169 @@ aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba"#
         assert_eq!(part_1(&rules, &messages), 2);
     }
 
+    #[test]
+    fn matcher_example() {
+        let (rules, messages) = parse_input(EXAMPLE_INPUT).unwrap();
+        let matcher = Matcher::new(&rules);
+        let matches: Vec<bool> = messages.iter().map(|m| matcher.is_match(m)).collect();
+        assert_eq!(matches, [true, false, true, false, false]);
+        assert!(matcher.matches_rule(1, "aaab"));
+        assert!(matcher.matches_rule(4, "a"));
+        assert!(!matcher.matches_rule(4, "b"));
+        assert!(!matcher.matches_rule(6, "a"));
+
+        // Same answers as the regex for the part 2 rules without loops
+        let (rules, messages) = parse_input(EXAMPLE_INPUT_2).unwrap();
+        let re = Regex::new(&rules2regex(&rules)).unwrap();
+        let matcher = Matcher::new(&rules);
+        for m in messages {
+            assert_eq!(matcher.is_match(m), re.is_match(m), "{}", m);
+        }
+    }
+
+    #[test]
+    fn matcher_recursive() {
+        // Left recursion, right recursion, nesting and empty alternatives
+        let rules = numbered(vec![
+            Composite(vec![vec![1, 2]]),
+            Composite(vec![vec![1, 3], vec![3]]),
+            Composite(vec![vec![3, 2, 4], vec![]]),
+            Literal("a"),
+            Literal("b"),
+            Composite(vec![vec![5, 3], vec![6]]),
+            Literal(""),
+        ]);
+        let matcher = Matcher::new(&rules);
+        let matching = ["a", "aaa", "aab", "aaabb", "aaaaabbb"];
+        let not_matching = ["", "b", "ab", "aabb", "aaabbb", "abab", "aba"];
+        for m in &matching {
+            assert!(matcher.is_match(m), "{}", m);
+        }
+        for m in &not_matching {
+            assert!(!matcher.is_match(m), "{}", m);
+        }
+        // `5: 5 3 | 6` is `a*`
+        for m in &["", "a", "aaaa"] {
+            assert!(matcher.matches_rule(5, m), "{}", m);
+        }
+        assert!(!matcher.matches_rule(5, "ab"));
+        // Missing rules match nothing
+        let rules = numbered(vec![Composite(vec![vec![1, 2], vec![1]]), Literal("a")]);
+        let matcher = Matcher::new(&rules);
+        assert!(matcher.is_match("a"));
+        assert!(!matcher.is_match("ab"));
+    }
+
+    /// Check that `d` is a valid derivation of `message[d.start..]` according to `rules`.
+    fn check_derivation(rules: &Rules, message: &str, d: &Derivation) {
+        assert_eq!(&message[d.start..d.start + d.text.len()], d.text);
+        match (&rules[&d.rule], d.alt) {
+            (Literal(s), None) => {
+                assert_eq!(*s, d.text);
+                assert!(d.children.is_empty());
+            }
+            (Composite(alts), Some(alt)) => {
+                let ids: Vec<usize> = d.children.iter().map(|c| c.rule).collect();
+                assert_eq!(ids, alts[alt]);
+                let mut pos = d.start;
+                for child in &d.children {
+                    assert_eq!(child.start, pos);
+                    check_derivation(rules, message, child);
+                    pos += child.text.len();
+                }
+                assert_eq!(pos, d.start + d.text.len());
+            }
+            _ => panic!("{:?} does not match rule {}", d, d.rule),
+        }
+    }
+
+    #[test]
+    fn explain_example() {
+        let (rules, _) = parse_input(EXAMPLE_INPUT).unwrap();
+        let matcher = Matcher::new(&rules);
+        let derivation = matcher.explain(0, "ababbb").unwrap();
+        check_derivation(&rules, "ababbb", &derivation);
+        assert_eq!(derivation.alt, Some(0));
+        assert_eq!(derivation.children[1].alt, Some(1));
+        assert_eq!(
+            derivation.to_string(),
+            r#"0: 4 1 5 "ababbb"
+├── 4: "a"
+├── 1: 3 2 "babb"
+│   ├── 3: 5 4 "ba"
+│   │   ├── 5: "b"
+│   │   └── 4: "a"
+│   └── 2: 5 5 "bb"
+│       ├── 5: "b"
+│       └── 5: "b"
+└── 5: "b""#
+        );
+
+        let failure = matcher.explain(0, "aaabbb").unwrap_err();
+        assert_eq!(
+            failure,
+            Failure {
+                position: 4,
+                expected: vec![(4, "a")]
+            }
+        );
+        assert_eq!(
+            failure.to_string(),
+            r#"no match beyond position 4: expected 4 ("a")"#
+        );
+        // Matches up to the last character
+        let failure = matcher.explain(0, "aaaabbb").unwrap_err();
+        assert_eq!(failure.position, 6);
+        assert_eq!(
+            failure.to_string(),
+            "no match beyond position 6: expected end of message"
+        );
+        // Ends too early
+        let failure = matcher.explain(0, "abab").unwrap_err();
+        assert_eq!(failure.position, 4);
+        assert_eq!(failure.expected, [(5, "b")]);
+
+        assert_eq!(matcher.explain(4, "a").unwrap().to_string(), r#"4: "a""#);
+        assert_eq!(matcher.explain(4, "ab").unwrap_err().position, 1);
+        assert_eq!(matcher.explain(4, "b").unwrap_err().expected, [(4, "a")]);
+        assert_eq!(matcher.explain(9, "a").unwrap_err().expected, []);
+    }
+
+    #[test]
+    fn explain_recursive() {
+        let (mut rules, messages) = parse_input(EXAMPLE_INPUT_2).unwrap();
+        rules.insert(8, Composite(vec![vec![42], vec![42, 8]]));
+        rules.insert(11, Composite(vec![vec![42, 31], vec![42, 11, 31]]));
+        let matcher = Matcher::new(&rules);
+        for m in messages {
+            match matcher.explain(0, m) {
+                Ok(d) => check_derivation(&rules, m, &d),
+                Err(f) => assert!(!matcher.is_match(m), "{}: {}", m, f),
+            }
+        }
+
+        // Cycles which match the same text
+        let rules = numbered(vec![
+            Composite(vec![vec![1], vec![2]]),
+            Composite(vec![vec![0], vec![3, 2]]),
+            Composite(vec![vec![], vec![3]]),
+            Literal("a"),
+        ]);
+        let matcher = Matcher::new(&rules);
+        for m in &["", "a", "aa"] {
+            check_derivation(&rules, m, &matcher.explain(0, m).unwrap());
+        }
+        assert_eq!(
+            matcher.explain(0, "aaa").unwrap_err(),
+            Failure {
+                position: 2,
+                expected: vec![]
+            }
+        );
+    }
+
     #[test]
     fn part_2_example() {
-        // FIXME: EXAMPLE_INTPUT_2 is missing some rules
         let (rules, messages) = parse_input(EXAMPLE_INPUT_2).unwrap();
         assert_eq!(part_2(&rules, &messages), 12);
     }
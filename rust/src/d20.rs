@@ -1,5 +1,11 @@
-use crate::str::{rsplit_once, split_once};
-use std::collections::HashSet;
+use crate::dihedral::{Side, Transform};
+use crate::grid::{self, Cell, Grid, Pos};
+use crate::parse::{Context, Located, ResultExt};
+use crate::solution::{Part, Solution};
+use crate::str::split_once;
+use crate::visualise::{Frame, Recording};
+use std::collections::HashMap;
+use Pixel::*;
 
 /// Key points:
 /// - Tiles are rotated **and** flipped
@@ -57,119 +63,452 @@ use std::collections::HashSet;
 ///     ```
 ///
 /// Solution:
-/// - Extract tile Ids and edges (first/last columns, top/bottom rows) from input
-/// - Compute the flipped versions (inverses) of the edges
+/// - Extract tile Ids and edges from input, encoding each edge as an integer with a bit per
+///   pixel (see `Edge`)
+/// - An edge matches another if it is equal to it or its reverse, so index the tiles by the
+///   *canonical* form of each of their edges: the lesser of the edge and its reverse
 /// - Find the corner tiles:
-///     - For each pair of tiles, calculate the set difference between the edges of the first tile
-///       and the transformations of the edges of the other tile (edges & their inverse).
-///     - If the resulting set contains only 2 edges -> the first tile in the pair is a corner
-///       tile.
+///     - Look up each edge of each tile in the index
+///     - If only 2 edges are shared with another tile -> corner tile
 /// - Return the product of the corner tile Ids
-fn part_1(tiles: &[Tile]) -> u64 {
+fn part_1(tiles: &[Tile]) -> Result<u64, Error> {
     // For good input that meets the above assumptions `collect`ing first into a `Vec` is not
-    // necessary (use `Iterator::product`). However, I wanted to sanity check that *exactly*
-    // 4 corner Ids had been found before returning a result to ensure errors in my assumptions
-    // and/or the input data were clearer.
-    let corner_ids: Vec<u64> = tiles
+    // necessary (use `Iterator::product`). However, I wanted to check that *exactly* 4 corner
+    // Ids had been found before returning a result to ensure errors in my assumptions and/or the
+    // input data were clearer.
+    let index = EdgeIndex::new(tiles);
+    let corner_ids: Vec<u64> = (0..tiles.len())
+        .filter(|&i| index.neighbours(tiles, i) == 2)
+        .map(|i| tiles[i].id)
+        .collect();
+
+    if corner_ids.len() != 4 {
+        return Err(Error::NoArrangement);
+    }
+
+    Ok(corner_ids.iter().product())
+}
+
+/// Key points:
+/// - The tiles must actually be assembled into the full image this time
+///     - Each tile can be in any of 8 orientations (4 rotations, each of which may be flipped)
+/// - The border of each tile is removed once assembled
+/// - Sea monsters only appear in one orientation of the image
+/// - Water roughness = number of `#` which are *not* part of a sea monster
+///     - Sea monsters may overlap, so count the `#`s covered by any sea monster
+///
+/// Solution:
+/// - Index the tiles by their edges to find the candidate neighbours of a tile without searching
+///   every tile (the border of each adjacent edge is unique, see `part_1`).
+/// - Start from any corner tile, oriented such that its unmatched edges are at the top and left
+///   so that it is the top left tile of the image.
+/// - Fill in the rest of the image row by row from left to right. Each tile must match the right
+///   edge of the tile to its left *and* the bottom edge of the tile above, so try each
+///   orientation of each unused tile sharing those edges. The edges of a tile in any orientation
+///   are a permutation of its original edges (reversed for reflections), so only the placed
+///   tiles need to actually be transformed.
+/// - Strip the borders and stitch the tiles together into a single image.
+/// - Search each orientation of the image for sea monsters, stopping at the first orientation
+///   which contains any.
+fn part_2(tiles: &[Tile]) -> Result<usize, Error> {
+    let image = stitch(&assemble(tiles)?);
+    let (image, monsters) = find_sea_monsters(&image).ok_or(Error::NoSeaMonsters)?;
+    Ok(roughness(&image, &monsters))
+}
+
+/// A tile in its position and orientation in the assembled image.
+#[derive(PartialEq, Eq, Debug, Clone)]
+struct Placement {
+    id: u64,
+    /// Orientation of the tile relative to its input
+    transform: Transform,
+    image: Grid<Pixel>,
+}
+
+/// Arrange `tiles` into a square, each tile matching all of its neighbours.
+fn assemble(tiles: &[Tile]) -> Result<Grid<Placement>, Error> {
+    let side = (1..=tiles.len())
+        .find(|n| n * n >= tiles.len())
+        .filter(|n| n * n == tiles.len())
+        .ok_or(Error::NotSquare(tiles.len()))?;
+    let size = tiles[0].image.width();
+    if tiles.iter().any(|t| t.image.width() != size) {
+        return Err(Error::MismatchedTileSizes);
+    }
+
+    let index = EdgeIndex::new(tiles);
+
+    // A corner has 2 neighbours, unless it is the only tile
+    let neighbours = if side == 1 { 0 } else { 2 };
+    let corner = (0..tiles.len())
+        .find(|&i| index.neighbours(tiles, i) == neighbours)
+        .ok_or(Error::NoArrangement)?;
+    let outer = |t| index.is_outer(tiles[corner].edge(t, Side::Top), corner)
+        && index.is_outer(tiles[corner].edge(t, Side::Left), corner);
+    let transform = Transform::ALL
         .iter()
-        .filter(|t1| {
-            t1.edges
-                .iter()
-                .filter(|&e| {
-                    tiles
-                        .iter()
-                        .filter(|t2| t1 != t2)
-                        .any(|t2| t2.possible_edges.contains(e))
-                })
-                .count()
-                == 2
+        .copied()
+        .find(|&t| outer(t))
+        .ok_or(Error::NoArrangement)?;
+
+    let mut used = vec![false; tiles.len()];
+    used[corner] = true;
+    let mut placed = vec![(corner, transform)];
+    for i in 1..tiles.len() {
+        let (x, y) = (i % side, i / side);
+        // Edges are read clockwise, so adjacent edges match when one is the reverse of the other
+        let facing = |(j, t): (usize, Transform), side| tiles[j].edge(t, side).reversed();
+        let left = (x > 0).then(|| facing(placed[i - 1], Side::Right));
+        let above = (y > 0).then(|| facing(placed[i - side], Side::Bottom));
+        let fits = |j: usize, t| {
+            left.is_none_or(|e| tiles[j].edge(t, Side::Left) == e)
+                && above.is_none_or(|e| tiles[j].edge(t, Side::Top) == e)
+        };
+        let next = index
+            .matches(left.or(above).unwrap())
+            .iter()
+            .filter(|&&j| !used[j])
+            .find_map(|&j| {
+                Transform::ALL
+                    .iter()
+                    .find(|&&t| fits(j, t))
+                    .map(|&t| (j, t))
+            })
+            .ok_or(Error::NoArrangement)?;
+        used[next.0] = true;
+        placed.push(next);
+    }
+
+    let placed = placed
+        .into_iter()
+        .map(|(i, transform)| Placement {
+            id: tiles[i].id,
+            transform,
+            image: transform.apply(&tiles[i].image),
         })
-        .map(|t| t.id)
         .collect();
+    Ok(Grid::new(side, side, placed).unwrap())
+}
+
+/// Tiles indexed by the canonical form of each of their edges.
+struct EdgeIndex(HashMap<Edge, Vec<usize>>);
+
+impl EdgeIndex {
+    fn new(tiles: &[Tile]) -> Self {
+        let mut index: HashMap<Edge, Vec<usize>> = HashMap::with_capacity(tiles.len() * 4);
+        for (i, tile) in tiles.iter().enumerate() {
+            for e in &tile.edges {
+                index.entry(e.canonical()).or_default().push(i);
+            }
+        }
+        EdgeIndex(index)
+    }
+
+    /// Tiles with an edge matching `edge` in either direction.
+    fn matches(&self, edge: Edge) -> &[usize] {
+        self.0.get(&edge.canonical()).map_or(&[], Vec::as_slice)
+    }
 
-    // Sanity check
-    assert_eq!(corner_ids.len(), 4);
+    /// Whether `edge` of `tile` does not match any other tile.
+    fn is_outer(&self, edge: Edge, tile: usize) -> bool {
+        self.matches(edge).iter().all(|&i| i == tile)
+    }
+
+    /// Number of edges of `tiles[tile]` which match another tile.
+    fn neighbours(&self, tiles: &[Tile], tile: usize) -> usize {
+        tiles[tile]
+            .edges
+            .iter()
+            .filter(|&&e| !self.is_outer(e, tile))
+            .count()
+    }
+}
 
-    corner_ids.iter().product()
+/// The assembled image with the border of each tile removed.
+fn stitch(layout: &Grid<Placement>) -> Grid<Pixel> {
+    let inner = layout[(0, 0)].image.width().saturating_sub(2);
+    let (width, height) = (layout.width() * inner, layout.height() * inner);
+    let pixels = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| layout[(x / inner, y / inner)].image[(x % inner + 1, y % inner + 1)])
+        .collect();
+    Grid::new(width, height, pixels).unwrap()
+}
+
+/// `grid` in each of its 8 orientations.
+fn orientations<T: Clone>(grid: &Grid<T>) -> impl Iterator<Item = (Transform, Grid<T>)> + '_ {
+    Transform::ALL.iter().map(move |&t| (t, t.apply(grid)))
 }
 
-fn part_2() {}
+const SEA_MONSTER: [&str; 3] = [
+    "                  # ",
+    "#    ##    ##    ###",
+    " #  #  #  #  #  #   ",
+];
 
-// FIXME: Improve error handling (ParseError::InvalidInput provides 0 useful information).
-fn parse_input(input: &str) -> Result<Vec<Tile>, ParseError> {
+/// Offsets of the `#`s of a sea monster from its top left corner.
+fn sea_monster() -> Vec<Pos> {
+    SEA_MONSTER
+        .iter()
+        .enumerate()
+        .flat_map(|(y, line)| {
+            line.char_indices()
+                .filter(|&(_, c)| c == '#')
+                .map(move |(x, _)| (x, y))
+        })
+        .collect()
+}
+
+/// The first orientation of `image` containing sea monsters and the top left corner of each
+/// sea monster in it.
+fn find_sea_monsters(image: &Grid<Pixel>) -> Option<(Grid<Pixel>, Vec<Pos>)> {
+    let monster = sea_monster();
+    let (width, height) = (SEA_MONSTER[0].len(), SEA_MONSTER.len());
+    orientations(image).find_map(|(_, image)| {
+        let monsters: Vec<Pos> = (0..(image.height() + 1).saturating_sub(height))
+            .flat_map(|y| (0..(image.width() + 1).saturating_sub(width)).map(move |x| (x, y)))
+            .filter(|&(x, y)| monster.iter().all(|&(dx, dy)| image[(x + dx, y + dy)] == On))
+            .collect();
+        if monsters.is_empty() {
+            None
+        } else {
+            Some((image, monsters))
+        }
+    })
+}
+
+/// Number of `#`s in `image` which are not part of any of the sea `monsters`.
+fn roughness(image: &Grid<Pixel>, monsters: &[Pos]) -> usize {
+    count_rough(&mark_sea_monsters(image, monsters))
+}
+
+fn count_rough(marked: &Frame) -> usize {
+    marked.cells().iter().filter(|&&c| c == On.to_char()).count()
+}
+
+/// `image` with the pixels of each of the sea `monsters` drawn as `O`, as in the puzzle
+/// description.
+fn mark_sea_monsters(image: &Grid<Pixel>, monsters: &[Pos]) -> Frame {
+    let mut marked = image.map(Cell::to_char);
+    for &(x, y) in monsters {
+        for &(dx, dy) in &sea_monster() {
+            marked[(x + dx, y + dy)] = 'O';
+        }
+    }
+    marked
+}
+
+/// The Id and orientation of the tile in each position of `layout`, with a line for each row
+/// of tiles.
+fn layout_map(layout: &Grid<Placement>) -> String {
+    let cells: Vec<String> = layout
+        .cells()
+        .iter()
+        .map(|p| format!("{} {:?}", p.id, p.transform))
+        .collect();
+    let width = cells.iter().map(String::len).max().unwrap_or(0);
+    cells
+        .chunks(layout.width())
+        .map(|row| {
+            let row: Vec<String> = row.iter().map(|c| format!("{:<1$}", c, width)).collect();
+            row.join("  ").trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn parse_input(input: &str) -> Result<Vec<Tile>, Located<ParseError>> {
+    let ctx = Context::new(input);
     input
         .trim() // Puzzle input has a trailing newline
         .split("\n\n")
         .map(|tile| {
-            let (heading, grid) = split_once(tile, "\n").ok_or(ParseError::InvalidInput)?;
-            let id = rsplit_once(heading, " ")
-                .and_then(|(id, _)| id.strip_suffix(":"))
-                .ok_or(ParseError::InvalidInput)
-                .and_then(|id| id.parse().map_err(|e| ParseError::InvalidId(e)))?;
-
-            let data: Vec<&str> = grid.lines().collect();
-            // Sanity check dimensions
-            let height = data.len();
-            let width = data[0].len();
-            if height == 0 || width != height || data.iter().any(|r| r.len() != width) {
-                return Err(ParseError::InvalidInput);
-            }
+            let (heading, grid) = split_once(tile, "\n")
+                .ok_or(ParseError::MissingImage)
+                .at(&ctx, tile)?;
+            let id = heading
+                .strip_prefix("Tile ")
+                .and_then(|id| id.strip_suffix(":"))
+                .ok_or(ParseError::InvalidHeading)
+                .at(&ctx, heading)?;
+            let id = id.parse().map_err(ParseError::InvalidId).at(&ctx, id)?;
 
-            // Unchecked indexing/unwraps OK because of above sanity checks
-            let edges: HashSet<String> = [
-                // Top row
-                data[0].to_string(),
-                // Bottom row
-                data[width - 1].to_string(),
-                // First col
-                data.iter().map(|r| r.chars().next().unwrap()).collect(),
-                // Last col
-                data.iter().map(|r| r.chars().last().unwrap()).collect(),
-            ]
-            .iter()
-            .cloned()
-            .collect();
+            let image: Grid<Pixel> = Grid::parse(&ctx, grid).map_err(|e| {
+                e.map(|e| match e {
+                    grid::ParseError::InvalidCharacter(c) => ParseError::InvalidPixel(c),
+                    grid::ParseError::EmptyInput | grid::ParseError::MalformedGrid => {
+                        ParseError::InvalidImageDimensions
+                    }
+                })
+            })?;
+            let size = image.width();
+            if image.height() != size || size > Edge::MAX_LEN {
+                return Err(ctx.error(grid, ParseError::InvalidImageDimensions));
+            }
 
-            let possible_edges = edges
-                .iter()
-                .cloned()
-                .chain(edges.iter().map(|e| e.chars().rev().collect()))
-                .collect();
-
-            Ok(Tile {
-                id,
-                edges,
-                possible_edges,
-            })
+            let edges = Side::ALL.map(|side| Edge::new(&side.edge(&image)));
+            Ok(Tile { id, edges, image })
         })
         .collect()
 }
 
-pub fn run(input: &str) {
-    let tiles = parse_input(input).expect("unable to parse input");
-    println!("Part 1: {}", part_1(&tiles));
-    // println!("Part 2: {}", part_2(&parsed));
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Parsed = Vec<Tile>;
+    type Answer1 = Result<u64, Error>;
+    type Answer2 = Result<usize, Error>;
+    type ParseError = Located<ParseError>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1(tiles: &Self::Parsed) -> Self::Answer1 {
+        part_1(tiles)
+    }
+
+    fn part_2(tiles: &Self::Parsed) -> Self::Answer2 {
+        part_2(tiles)
+    }
+
+    /// The assembled image and the layout of the tiles for part 1, and the image with the sea
+    /// monsters marked as `O` for part 2.
+    fn visualise(tiles: &Self::Parsed, part: Part, recording: &mut Recording) -> bool {
+        let layout = match assemble(tiles) {
+            Ok(layout) => layout,
+            Err(e) => {
+                recording.note(|| format!("Unable to assemble the tiles: {:?}", e));
+                return true;
+            }
+        };
+        let image = stitch(&layout);
+        match part {
+            Part::One => {
+                recording.record(|| image.map(Cell::to_char));
+                recording.note(|| layout_map(&layout));
+            }
+            Part::Two => match find_sea_monsters(&image) {
+                Some((image, monsters)) => {
+                    let marked = mark_sea_monsters(&image, &monsters);
+                    let roughness = count_rough(&marked);
+                    recording.record(|| marked);
+                    recording.note(|| {
+                        format!(
+                            "{} sea monsters, water roughness {}",
+                            monsters.len(),
+                            roughness
+                        )
+                    });
+                }
+                None => {
+                    recording.record(|| image.map(Cell::to_char));
+                    recording.note(|| "No sea monsters found".to_string());
+                }
+            },
+        }
+        true
+    }
 }
 
 #[derive(PartialEq, Eq, Debug)]
-enum ParseError {
-    InvalidInput,
+pub enum ParseError {
+    /// Heading was not of the form `Tile <id>:`
+    InvalidHeading,
     InvalidId(std::num::ParseIntError),
+    /// Heading was not followed by any image data
+    MissingImage,
+    /// Image was not a non-empty square of at most `Edge::MAX_LEN` pixels
+    InvalidImageDimensions,
+    InvalidPixel(char),
+}
+
+impl_error!(parse ParseError);
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum Error {
+    /// The number of tiles was not a square number
+    NotSquare(usize),
+    /// Tiles were not all the same size
+    MismatchedTileSizes,
+    /// The tiles could not be arranged such that all adjacent edges match
+    NoArrangement,
+    /// No orientation of the image contains a sea monster
+    NoSeaMonsters,
+}
+
+impl_error!(solve Error);
+
+char_enum! {
+    Pixel {
+        (On, '#'),
+        (Off, '.'),
+    }
 }
 
 #[derive(PartialEq, Eq, Debug)]
-struct Tile {
+pub struct Tile {
     id: u64,
-    /// First/last columns, top/bottom rows
-    edges: HashSet<String>,
-    /// First/last columns, top/bottom rows *and* their inverses. Computed in advance
-    /// and stored on `Tile` struct to avoid re-creating it multiple times in `part_1`.
-    possible_edges: HashSet<String>,
+    /// Top, right, bottom and left edges, each read clockwise (see `dihedral::Side::edge`)
+    edges: [Edge; 4],
+    image: Grid<Pixel>,
+}
+
+impl Tile {
+    /// The edge on `side` of the tile once oriented by `transform`, read clockwise.
+    fn edge(&self, transform: Transform, side: Side) -> Edge {
+        let edge = self.edges[transform.inverse().apply_side(side) as usize];
+        if transform.is_reflection() {
+            edge.reversed()
+        } else {
+            edge
+        }
+    }
+}
+
+/// A tile edge packed into an integer, with a bit per pixel (`#` = `1`) and the first pixel as the
+/// most significant bit e.g. `#...##.#..` = `0b1000110100`.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Clone, Copy)]
+struct Edge {
+    bits: u32,
+    len: u8,
+}
+
+impl Edge {
+    /// Length of the longest edge which can be packed.
+    const MAX_LEN: usize = 32;
+
+    fn new(pixels: &[Pixel]) -> Self {
+        Edge {
+            bits: pixels
+                .iter()
+                .fold(0, |bits, &p| (bits << 1) | (p == On) as u32),
+            len: pixels.len() as u8,
+        }
+    }
+
+    /// The edge read in the opposite direction, as seen when the tile is flipped.
+    fn reversed(self) -> Self {
+        Edge {
+            bits: self
+                .bits
+                .reverse_bits()
+                .checked_shr(32 - self.len as u32)
+                .unwrap_or(0),
+            len: self.len,
+        }
+    }
+
+    /// The same key for an edge and its reverse, so edges can be matched regardless of direction.
+    fn canonical(self) -> Self {
+        self.min(self.reversed())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashSet;
 
     const EXAMPLE_INPUT: &str = "\
 Tile 2311:
@@ -280,6 +619,10 @@ Tile 3079:
 ..#.......
 ..#.###...";
 
+    fn edge(s: &str) -> Edge {
+        Edge::new(&s.chars().map(|c| Pixel::new(c).unwrap()).collect::<Vec<_>>())
+    }
+
     #[test]
     fn parse_input_example() {
         // First 2 tiles from example input,
@@ -309,52 +652,245 @@ Tile 1951:
 #...##.#..";
 
         assert_eq!(
-            parse_input(&input).unwrap(),
+            parse_input(input).unwrap(),
             [
                 Tile {
                     id: 2311,
-                    edges: [".#####..#.", "...#.##..#", "..##.#..#.", "..###..###"]
-                        .iter()
-                        .map(|s| s.to_string())
-                        .collect(),
-                    #[rustfmt::skip]
-                    possible_edges: [
-                        // Normal edges
-                        ".#####..#.", "...#.##..#", "..##.#..#.", "..###..###",
-                        // Flipped edges
-                        ".#..#####.", "#..##.#...", ".#..#.##..", "###..###..",
-                    ]
-                        .iter()
-                        .map(|s| s.to_string())
-                        .collect(),
+                    edges: ["..##.#..#.", "...#.##..#", "###..###..", ".#..#####."].map(edge),
+                    image: input[11..120].parse().unwrap(),
                 },
                 Tile {
                     id: 1951,
-                    edges: [".#####..#.", "#...##.#..", "#.##...##.", "##.#..#..#"]
-                        .iter()
-                        .map(|s| s.to_string())
-                        .collect(),
-                    #[rustfmt::skip]
-                    possible_edges: [
-                        // Normal edges
-                        ".#####..#.", "#...##.#..", "#.##...##.", "##.#..#..#",
-                        // Flipped edges
-                        ".#..#####.", "..#.##...#", ".##...##.#", "#..#..#.##",
-                    ]
-                        .iter()
-                        .map(|s| s.to_string())
-                        .collect(),
+                    edges: ["#.##...##.", ".#####..#.", "..#.##...#", "#..#..#.##"].map(edge),
+                    image: input[133..].parse().unwrap(),
                 }
             ]
         );
     }
 
+    #[test]
+    fn parse_input_invalid() {
+        let error = |input| parse_input(input).unwrap_err().error;
+        assert_eq!(error("Tile 1\n#."), ParseError::InvalidHeading);
+        assert_eq!(error("Tile 1:"), ParseError::MissingImage);
+        assert_eq!(error("Tile 1:\n#.\n#"), ParseError::InvalidImageDimensions);
+        assert_eq!(error("Tile 1:\n#.\n#x"), ParseError::InvalidPixel('x'));
+        assert!(matches!(error("Tile x:\n#"), ParseError::InvalidId(_)));
+    }
+
+    #[test]
+    fn edges() {
+        let e = edge("#...##.#..");
+        assert_eq!(e.bits, 0b1000110100);
+        assert_eq!(e.reversed(), edge("..#.##...#"));
+        assert_eq!(e.reversed().reversed(), e);
+        assert_eq!(e.canonical(), e.reversed().canonical());
+        assert_eq!(e.canonical(), edge("..#.##...#"));
+        assert_eq!(edge("#").reversed(), edge("#"));
+        let long = edge(&"#.".repeat(16));
+        assert_eq!(long.reversed(), edge(&".#".repeat(16)));
+    }
+
+    #[test]
+    fn oriented_edges() {
+        let tiles = parse_input(EXAMPLE_INPUT).unwrap();
+        for tile in &tiles {
+            for &t in Transform::ALL.iter() {
+                let image = t.apply(&tile.image);
+                for &side in Side::ALL.iter() {
+                    assert_eq!(tile.edge(t, side), Edge::new(&side.edge(&image)));
+                }
+            }
+        }
+    }
+
     #[test]
     fn part_1_example() {
         let tiles = parse_input(EXAMPLE_INPUT).unwrap();
-        assert_eq!(part_1(&tiles), 20899048083289);
+        assert_eq!(part_1(&tiles), Ok(20899048083289));
+    }
+
+    #[test]
+    fn part_1_single_tile() {
+        let tiles = parse_input("Tile 7:\n#.\n..").unwrap();
+        assert_eq!(part_1(&tiles), Err(Error::NoArrangement));
     }
 
     #[test]
-    fn part_2_example() {}
+    fn assemble_example() {
+        let tiles = parse_input(EXAMPLE_INPUT).unwrap();
+        let layout = assemble(&tiles).unwrap();
+        assert_eq!((layout.width(), layout.height()), (3, 3));
+        let corners: HashSet<u64> = [(0, 0), (2, 0), (0, 2), (2, 2)]
+            .iter()
+            .map(|&pos| layout[pos].id)
+            .collect();
+        assert_eq!(corners, [1951, 3079, 2971, 1171].iter().copied().collect());
+        assert_eq!(layout[(1, 1)].id, 1427);
+        for (_, placement) in layout.iter() {
+            let tile = tiles.iter().find(|t| t.id == placement.id).unwrap();
+            assert_eq!(placement.transform.apply(&tile.image), placement.image);
+        }
+
+        let image = stitch(&layout);
+        assert_eq!((image.width(), image.height()), (24, 24));
+    }
+
+    /// A `side`x`side` puzzle of randomly oriented and shuffled tiles, and its assembled image.
+    fn random_puzzle(side: usize, size: usize) -> (Vec<Tile>, Grid<Pixel>) {
+        let mut x: u32 = 1;
+        let mut random = move || {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x
+        };
+        // Adjacent tiles overlap by a row/column, so their edges are the same pixels
+        let width = side * (size - 1) + 1;
+        let pixels = (0..width * width)
+            .map(|_| if random() % 2 == 0 { On } else { Off })
+            .collect();
+        let pixels = Grid::new(width, width, pixels).unwrap();
+        let images: Vec<Grid<Pixel>> = (0..side * side)
+            .map(|i| {
+                let (x0, y0) = ((i % side) * (size - 1), (i / side) * (size - 1));
+                let cells = (0..size)
+                    .flat_map(|y| (0..size).map(move |x| (x0 + x, y0 + y)))
+                    .map(|pos| pixels[pos])
+                    .collect();
+                Grid::new(size, size, cells).unwrap()
+            })
+            .collect();
+        let layout = images
+            .iter()
+            .map(|image| Placement {
+                id: 0,
+                transform: Transform::Identity,
+                image: image.clone(),
+            })
+            .collect();
+        let expected = stitch(&Grid::new(side, side, layout).unwrap());
+
+        let mut tiles: Vec<(u32, Tile)> = images
+            .iter()
+            .enumerate()
+            .map(|(i, image)| {
+                let image = Transform::ALL[random() as usize % 8].apply(image);
+                let edges = Side::ALL.map(|side| Edge::new(&side.edge(&image)));
+                let tile = Tile {
+                    id: i as u64 + 1,
+                    edges,
+                    image,
+                };
+                (random(), tile)
+            })
+            .collect();
+        tiles.sort_by_key(|(key, _)| *key);
+        (tiles.into_iter().map(|(_, t)| t).collect(), expected)
+    }
+
+    #[test]
+    fn assemble_large() {
+        let (tiles, expected) = random_puzzle(16, Edge::MAX_LEN);
+        let image = stitch(&assemble(&tiles).unwrap());
+        assert!(orientations(&expected).any(|(_, o)| o == image));
+        assert_eq!(part_1(&tiles), Ok([1, 16, 241, 256].iter().product()));
+    }
+
+    #[test]
+    fn find_sea_monsters_example() {
+        let tiles = parse_input(EXAMPLE_INPUT).unwrap();
+        let (image, monsters) = find_sea_monsters(&stitch(&assemble(&tiles).unwrap())).unwrap();
+        assert_eq!(
+            image.to_string(),
+            "\
+.####...#####..#...###..
+#####..#..#.#.####..#.#.
+.#.#...#.###...#.##.##..
+#.#.##.###.#.##.##.#####
+..##.###.####..#.####.##
+...#.#..##.##...#..#..##
+#.##.#..#.#..#..##.#.#..
+.###.##.....#...###.#...
+#.####.#.#....##.#..#.#.
+##...#..#....#..#...####
+..#.##...###..#.#####..#
+....#.##.#.#####....#...
+..##.##.###.....#.##..#.
+#...#...###..####....##.
+.#.##...#.##.#.#.###...#
+#.###.#..####...##..#...
+#.###...#.##...#.######.
+.###.###.#######..#####.
+..##.#..#..#.#######.###
+#.#..##.########..#..##.
+#.#####..#.#...##..#....
+#....##..#.#########..##
+#...#.....#..##...###.##
+#..###....##.#...##.##.#"
+        );
+        assert_eq!(monsters, [(2, 2), (1, 16)]);
+    }
+
+    #[test]
+    fn visualise() {
+        let tiles = parse_input(EXAMPLE_INPUT).unwrap();
+        let mut recording = Recording::new();
+        assert!(Solver::visualise(&tiles, Part::One, &mut recording));
+        assert_eq!(recording.frames().len(), 1);
+        assert_eq!(
+            recording.notes(),
+            ["\
+1951 Rotate90   2729 Rotate90   2971 Rotate90
+2311 Rotate90   1427 Rotate90   1489 Rotate90
+3079 Transpose  2473 Rotate180  1171 Rotate270"]
+        );
+
+        let mut recording = Recording::new();
+        assert!(Solver::visualise(&tiles, Part::Two, &mut recording));
+        assert_eq!(
+            recording.frames()[0].to_string(),
+            "\
+.####...#####..#...###..
+#####..#..#.#.####..#.#.
+.#.#...#.###...#.##.O#..
+#.O.##.OO#.#.OO.##.OOO##
+..#O.#O#.O##O..O.#O##.##
+...#.#..##.##...#..#..##
+#.##.#..#.#..#..##.#.#..
+.###.##.....#...###.#...
+#.####.#.#....##.#..#.#.
+##...#..#....#..#...####
+..#.##...###..#.#####..#
+....#.##.#.#####....#...
+..##.##.###.....#.##..#.
+#...#...###..####....##.
+.#.##...#.##.#.#.###...#
+#.###.#..####...##..#...
+#.###...#.##...#.##O###.
+.O##.#OO.###OO##..OOO##.
+..O#.O..O..O.#O##O##.###
+#.#..##.########..#..##.
+#.#####..#.#...##..#....
+#....##..#.#########..##
+#...#.....#..##...###.##
+#..###....##.#...##.##.#"
+        );
+        assert_eq!(recording.notes(), ["2 sea monsters, water roughness 273"]);
+    }
+
+    #[test]
+    fn part_2_example() {
+        let tiles = parse_input(EXAMPLE_INPUT).unwrap();
+        assert_eq!(part_2(&tiles), Ok(273));
+    }
+
+    #[test]
+    fn part_2_invalid() {
+        let tiles = parse_input(EXAMPLE_INPUT).unwrap();
+        assert_eq!(part_2(&tiles[..8]), Err(Error::NotSquare(8)));
+        let tiles = parse_input("Tile 1:\n#.\n.#\n\nTile 2:\n#").unwrap();
+        assert_eq!(part_2(&tiles[..1]), Err(Error::NoSeaMonsters));
+        assert_eq!(part_2(&tiles[..0]), Err(Error::NotSquare(0)));
+    }
 }
@@ -1,5 +1,7 @@
+use crate::parse::{Context, Located, ResultExt};
+use crate::solution::Solution;
 use crate::str::split_once;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 
 /// Key points:
 /// - Each allergen is found in **exactly 1** ingredient
@@ -118,19 +120,22 @@ fn part_2(freqs: &Frequencies) -> String {
     res.join(",")
 }
 
-// TODO: Lots more room for error handling improvements
-fn parse_input(input: &str) -> Result<Frequencies, ParseError> {
+fn parse_input(input: &str) -> Result<Frequencies<'_>, Located<ParseError>> {
+    let ctx = Context::new(input);
     let mut allergen_freqs_by_ingredient: HashMap<&str, HashMap<&str, usize>> = HashMap::new();
     let mut allergen_total_freqs: HashMap<&str, usize> = HashMap::new();
     let mut ingredient_total_freqs: HashMap<&str, usize> = HashMap::new();
 
     for l in input.lines() {
         let (ingredients_part, allergens_part) =
-            split_once(l, " (contains ").ok_or(ParseError::InvalidInput)?;
+            split_once(l, " (contains ")
+                .ok_or(ParseError::InvalidInput)
+                .at(&ctx, l)?;
         let ingredients = ingredients_part.split(" ").collect::<Vec<_>>();
         let allergens = allergens_part
             .strip_suffix(")")
-            .ok_or(ParseError::InvalidInput)?
+            .ok_or(ParseError::InvalidInput)
+            .at(&ctx, allergens_part)?
             .split(", ")
             .collect::<Vec<_>>();
         for a in &allergens {
@@ -138,9 +143,7 @@ fn parse_input(input: &str) -> Result<Frequencies, ParseError> {
         }
         for i in ingredients {
             *ingredient_total_freqs.entry(i).or_insert(0) += 1;
-            let m = allergen_freqs_by_ingredient
-                .entry(i)
-                .or_insert(HashMap::new());
+            let m = allergen_freqs_by_ingredient.entry(i).or_default();
             for a in &allergens {
                 *m.entry(a).or_insert(0) += 1;
             }
@@ -154,14 +157,29 @@ fn parse_input(input: &str) -> Result<Frequencies, ParseError> {
     })
 }
 
-pub fn run(input: &str) {
-    let freqs = parse_input(input).expect("unable to parse input");
-    println!("Part 1: {}", part_1(&freqs));
-    println!("Part 2: {}", part_2(&freqs));
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Parsed = Frequencies<'a>;
+    type Answer1 = usize;
+    type Answer2 = String;
+    type ParseError = Located<ParseError>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1(freqs: &Self::Parsed) -> Self::Answer1 {
+        part_1(freqs)
+    }
+
+    fn part_2(freqs: &Self::Parsed) -> Self::Answer2 {
+        part_2(freqs)
+    }
 }
 
 #[derive(PartialEq, Eq, Debug)]
-struct Frequencies<'a> {
+pub struct Frequencies<'a> {
     /// `{ingredient: {allergen: frequency}}`
     allergen_by_ingredient: HashMap<&'a str, HashMap<&'a str, usize>>,
     /// `{allergen: frequency}`
@@ -183,10 +201,12 @@ impl<'a> Frequencies<'a> {
 }
 
 #[derive(PartialEq, PartialOrd, Eq, Ord, Debug)]
-enum ParseError {
+pub enum ParseError {
     InvalidInput,
 }
 
+impl_error!(parse ParseError);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,3 +1,5 @@
+use crate::parse::{Context, Located, ResultExt};
+use crate::solution::Solution;
 use crate::str::split_once;
 use std::collections::{HashSet, VecDeque};
 
@@ -156,35 +158,58 @@ fn play_game(p1: &mut Deck, p2: &mut Deck, mut prev_states: HashSet<(Deck, Deck)
     }
 }
 
-fn parse_input(input: &str) -> Result<(Deck, Deck), ParseError> {
-    let (p1_part, p2_part) = split_once(input, "\n\n").ok_or(ParseError::InvalidInput)?;
-    let p1 = p1_part
-        .lines()
-        .skip(1) // "Player 1:"
-        .map(|l| l.parse::<u8>())
-        .collect::<Result<VecDeque<_>, _>>()
-        .map_err(|e| ParseError::InvalidCard(e))?;
-    let p2 = p2_part
-        .lines()
-        .skip(1) // "Player 2:"
-        .map(|l| l.parse::<u8>())
-        .collect::<Result<VecDeque<_>, _>>()
-        .map_err(|e| ParseError::InvalidCard(e))?;
+fn parse_input(input: &str) -> Result<(Deck, Deck), Located<ParseError>> {
+    let ctx = Context::new(input);
+    let (p1_part, p2_part) = split_once(input, "\n\n")
+        .ok_or(ParseError::InvalidInput)
+        .at(&ctx, ctx.end())?;
+    let parse_deck = |part: &str| {
+        let deck = part
+            .lines()
+            .skip(1) // "Player N:"
+            .map(|l| match l.parse::<u8>() {
+                // A sub-game of 0 cards would have no cards to play
+                Ok(0) => Err(ctx.error(l, ParseError::ZeroCard)),
+                card => card.map_err(ParseError::InvalidCard).at(&ctx, l),
+            })
+            .collect::<Result<VecDeque<_>, _>>()?;
+        if deck.is_empty() {
+            return Err(ctx.error(part, ParseError::EmptyDeck));
+        }
+        Ok(deck)
+    };
+    let p1 = parse_deck(p1_part)?;
+    let p2 = parse_deck(p2_part)?;
     // TODO: More sanity checks? e.g. complete set of cards present (contiguous set from 1..n)
     if p1.len() != p2.len() {
-        return Err(ParseError::MissingCards);
+        return Err(Located::new(ParseError::MissingCards));
     }
 
     Ok((p1, p2))
 }
 
-pub fn run(input: &str) {
-    let (p1, p2) = parse_input(input).expect("unable to parse input");
-    println!("Part 1: {}", part_1(p1.clone(), p2.clone()));
-    println!("Part 2: {}", part_2(&mut p1.clone(), &mut p2.clone()));
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Parsed = (Deck, Deck);
+    type Answer1 = u16;
+    type Answer2 = u16;
+    type ParseError = Located<ParseError>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1((p1, p2): &Self::Parsed) -> Self::Answer1 {
+        part_1(p1.clone(), p2.clone())
+    }
+
+    fn part_2((p1, p2): &Self::Parsed) -> Self::Answer2 {
+        part_2(&mut p1.clone(), &mut p2.clone())
+    }
 }
 
-type Deck = VecDeque<u8>;
+pub type Deck = VecDeque<u8>;
 
 #[derive(PartialEq, Eq, Debug)]
 enum Players {
@@ -193,12 +218,17 @@ enum Players {
 }
 
 #[derive(PartialEq, Eq, Debug)]
-enum ParseError {
+pub enum ParseError {
     InvalidInput,
     InvalidCard(std::num::ParseIntError),
+    ZeroCard,
     MissingCards,
+    /// A player was not dealt any cards
+    EmptyDeck,
 }
 
+impl_error!(parse ParseError);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -240,4 +270,19 @@ Player 2:
         let (mut p1, mut p2) = parse_input(EXAMPLE_INPUT).unwrap();
         assert_eq!(part_2(&mut p1, &mut p2), 291);
     }
+
+    #[test]
+    fn parse_input_invalid() {
+        let error = |input| parse_input(input).unwrap_err().error;
+        assert_eq!(error(""), ParseError::InvalidInput);
+        assert_eq!(error("1\n\n2"), ParseError::EmptyDeck);
+        assert_eq!(error("Player 1:\n\nPlayer 2:"), ParseError::EmptyDeck);
+        assert_eq!(error("Player 1:\n1\n\nPlayer 2:"), ParseError::EmptyDeck);
+        assert_eq!(error("Player 1:\n0\n\nPlayer 2:\n1"), ParseError::ZeroCard);
+        assert_eq!(error("Player 1:\n1\n2\n\nPlayer 2:\n3"), ParseError::MissingCards);
+
+        let e = parse_input("Player 1:\n1\n\nPlayer 2:\n").unwrap_err();
+        let location = e.location.unwrap();
+        assert_eq!((location.line, location.col), (4, 1));
+    }
 }
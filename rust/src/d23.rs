@@ -1,3 +1,8 @@
+use crate::grid::Grid;
+use crate::parse::{Context, Located};
+use crate::solution::{Part, Solution};
+use crate::visualise::{Frame, Recording};
+
 // TODO: The game state and circular list representation could be wrappeed up in
 // structs to ensure their respective invariants are maintained and to provide a better
 // API. This works though and I've spent too much time on it as is! :D
@@ -103,12 +108,19 @@
 /// ```
 /// No tricks/patterns, just simulation using the described circular list
 /// representation.
+#[allow(clippy::tabs_in_doc_comments)]
 fn part_1(cup_labels: &[usize]) -> String {
-    let max_cup = cup_labels.iter().max().unwrap().to_owned() as usize;
-    let starting_cup = cup_labels[0] as usize;
+    let max_cup = cup_labels.iter().max().unwrap().to_owned();
+    let starting_cup = cup_labels[0];
     let mut cups = build_circular_list(cup_labels);
 
-    play(&mut cups, starting_cup, max_cup, 100);
+    play(
+        &mut cups,
+        starting_cup,
+        max_cup,
+        100,
+        &mut Recording::disabled(),
+    );
 
     let mut traversal = vec![];
     let mut curr = cups[0];
@@ -139,7 +151,13 @@ fn part_2(cup_labels: &[usize]) -> u64 {
             .collect::<Vec<_>>(),
     );
 
-    play(&mut cups, starting_cup, max_cup, 10000000);
+    play(
+        &mut cups,
+        starting_cup,
+        max_cup,
+        10000000,
+        &mut Recording::disabled(),
+    );
 
     let c1 = next_cup(&cups, 1);
     let c2 = next_cup(&cups, c1);
@@ -155,19 +173,27 @@ fn build_circular_list(cup_labels: &[usize]) -> Vec<usize> {
     for (i, cup) in cup_labels[..n - 1].iter().enumerate() {
         cups[cup - 1] = cup_labels[i + 1];
     }
-    cups[cup_labels[n - 2]] = cup_labels[0];
+    cups[cup_labels[n - 1] - 1] = cup_labels[0];
 
     cups
 }
 
-fn play(cups: &mut [usize], starting_cup: usize, max_cup: usize, rounds: usize) {
+/// Play `rounds` moves, recording the circle before each move and after the last.
+fn play(
+    cups: &mut [usize],
+    starting_cup: usize,
+    max_cup: usize,
+    rounds: usize,
+    recording: &mut Recording,
+) {
     let mut current_cup = starting_cup;
+    recording.record(|| frame(cups, current_cup));
     for _ in 0..rounds {
         // "Take" 3 cups
-        let c1 = next_cup(&cups, current_cup);
-        let c2 = next_cup(&cups, c1);
-        let c3 = next_cup(&cups, c2);
-        let c3_next = next_cup(&cups, c3);
+        let c1 = next_cup(cups, current_cup);
+        let c2 = next_cup(cups, c1);
+        let c3 = next_cup(cups, c2);
+        let c3_next = next_cup(cups, c3);
 
         let dest_cup = {
             let mut dest = current_cup;
@@ -189,9 +215,25 @@ fn play(cups: &mut [usize], starting_cup: usize, max_cup: usize, rounds: usize)
         cups[c3 - 1] = old_dest;
 
         current_cup = c3_next;
+        recording.record(|| frame(cups, current_cup));
     }
 }
 
+/// The labels of the cups clockwise from the current cup, on a single row. Labels above `9` are
+/// drawn as `?`, so this is only useful for the small circle of `part_1`.
+fn frame(cups: &[usize], current_cup: usize) -> Frame {
+    let mut labels = Vec::with_capacity(cups.len());
+    let mut curr = current_cup;
+    loop {
+        labels.push(std::char::from_digit(curr as u32, 10).unwrap_or('?'));
+        curr = next_cup(cups, curr);
+        if curr == current_cup {
+            break;
+        }
+    }
+    Grid::new(labels.len(), 1, labels).unwrap()
+}
+
 #[inline]
 fn next_cup(cups: &[usize], label: usize) -> usize {
     cups[label - 1]
@@ -200,32 +242,93 @@ fn next_cup(cups: &[usize], label: usize) -> usize {
 /// Note: Using `usize` to make the circular list representation easier to build and
 /// work with. For part 2, this will only work on 32-bit or greater machines due to
 /// requiring `usize` to hold up to `1000000` for indexing.
-fn parse_input(input: &str) -> Result<Vec<usize>, ParseError> {
-    input
+///
+/// The labels must be each of `1..=n` exactly once for the circular list, and there must be
+/// enough cups for a move to have a destination.
+fn parse_input(input: &str) -> Result<Vec<usize>, Located<ParseError>> {
+    let ctx = Context::new(input);
+    let l = input
         .lines()
         .next()
-        .ok_or(ParseError::InvalidInput)?
-        .chars()
-        .map(|d| {
-            d.to_digit(10)
+        .filter(|l| !l.is_empty())
+        .ok_or_else(|| ctx.error(ctx.end(), ParseError::InvalidInput))?;
+    let mut seen = [false; 10];
+    let cup_labels = l
+        .char_indices()
+        .map(|(i, d)| {
+            let span = &l[i..i + d.len_utf8()];
+            let label = d
+                .to_digit(10)
                 .map(|x| x as usize)
-                .ok_or(ParseError::InvalidLabel(d))
+                .ok_or_else(|| ctx.error(span, ParseError::InvalidLabel(d)))?;
+            if std::mem::replace(&mut seen[label], true) {
+                return Err(ctx.error(span, ParseError::DuplicateLabel(label)));
+            }
+            Ok(label)
         })
-        .collect()
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let n = cup_labels.len();
+    if n < MIN_CUPS {
+        return Err(ctx.error(l, ParseError::TooFewCups(n)));
+    }
+    if let Some(missing) = (1..=n).find(|&label| !seen[label]) {
+        return Err(ctx.error(l, ParseError::MissingLabel(missing)));
+    }
+    Ok(cup_labels)
 }
 
-pub fn run(input: &str) {
-    let cup_labels = parse_input(input).expect("unable to parse input");
-    println!("Part 1: {}", part_1(&cup_labels));
-    println!("Part 2: {}", part_2(&cup_labels));
+/// The current cup, the 3 picked up cups and at least one destination cup.
+const MIN_CUPS: usize = 5;
+
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Parsed = Vec<usize>;
+    type Answer1 = String;
+    type Answer2 = u64;
+    type ParseError = Located<ParseError>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1(cup_labels: &Self::Parsed) -> Self::Answer1 {
+        part_1(cup_labels)
+    }
+
+    fn part_2(cup_labels: &Self::Parsed) -> Self::Answer2 {
+        part_2(cup_labels)
+    }
+
+    /// The 100 moves of part 1. Part 2 has far too many cups (and moves) to watch.
+    fn visualise(cup_labels: &Self::Parsed, part: Part, recording: &mut Recording) -> bool {
+        match part {
+            Part::One => {
+                let max_cup = cup_labels.iter().max().unwrap().to_owned();
+                let mut cups = build_circular_list(cup_labels);
+                play(&mut cups, cup_labels[0], max_cup, 100, recording);
+                true
+            }
+            Part::Two => false,
+        }
+    }
 }
 
 #[derive(PartialEq, Eq, Debug)]
-enum ParseError {
+pub enum ParseError {
     InvalidInput,
     InvalidLabel(char),
+    /// More than one cup with the same label
+    DuplicateLabel(usize),
+    /// The labels were not `1..=n` for `n` cups
+    MissingLabel(usize),
+    /// Fewer than `MIN_CUPS` cups
+    TooFewCups(usize),
 }
 
+impl_error!(parse ParseError);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -240,18 +343,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_input_invalid() {
+        let error = |input| parse_input(input).unwrap_err().error;
+        assert_eq!(error(""), ParseError::InvalidInput);
+        assert_eq!(error("\n"), ParseError::InvalidInput);
+        assert_eq!(error("3x1"), ParseError::InvalidLabel('x'));
+        assert_eq!(error("0"), ParseError::TooFewCups(1));
+        assert_eq!(error("1\n\n2"), ParseError::TooFewCups(1));
+        assert_eq!(error("2143"), ParseError::TooFewCups(4));
+        assert_eq!(error("124573"), ParseError::MissingLabel(6));
+        assert_eq!(error("123405"), ParseError::MissingLabel(6));
+        assert_eq!(error("12345179"), ParseError::DuplicateLabel(1));
+
+        let e = parse_input("3412325").unwrap_err();
+        assert_eq!(e.error, ParseError::DuplicateLabel(3));
+        let location = e.location.unwrap();
+        assert_eq!((location.line, location.col, location.len), (1, 5, 1));
+    }
+
     #[test]
     fn build_cups_example() {
         let cup_labels = parse_input(EXAMPLE_INPUT).unwrap();
         assert_eq!(
-            build_circular_list(
-                &cup_labels
-                    .iter()
-                    .map(|x| *x as usize)
-                    .collect::<Vec<usize>>()
-            ),
+            build_circular_list(&cup_labels),
             [2, 5, 8, 6, 4, 7, 3, 9, 1]
         );
+        assert_eq!(build_circular_list(&[2, 5, 1, 4, 3]), [4, 5, 2, 3, 1]);
     }
 
     #[test]
@@ -265,4 +383,16 @@ mod tests {
         let cup_labels = parse_input(EXAMPLE_INPUT).unwrap();
         assert_eq!(part_2(&cup_labels), 149245887792);
     }
+
+    #[test]
+    fn visualise() {
+        let cup_labels = parse_input(EXAMPLE_INPUT).unwrap();
+        let mut recording = Recording::new();
+        assert!(Solver::visualise(&cup_labels, Part::One, &mut recording));
+        let frames = recording.frames();
+        assert_eq!(frames.len(), 101);
+        assert_eq!(frames[0].to_string(), "389125467");
+        assert_eq!(frames[1].to_string(), "289154673");
+        assert!(!Solver::visualise(&cup_labels, Part::Two, &mut recording));
+    }
 }
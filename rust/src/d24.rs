@@ -1,3 +1,8 @@
+use crate::automaton::{Automaton, Neighbourhood, Rule};
+use crate::grid::Grid;
+use crate::parse::{Context, Located};
+use crate::solution::{Part, Solution};
+use crate::visualise::{Frame, Recording, BACKGROUND};
 use std::collections::HashSet;
 use Direction::*;
 const REFERENCE_TILE: Coord3 = Coord3 { x: 0, y: 0, z: 0 };
@@ -61,7 +66,7 @@ const REFERENCE_TILE: Coord3 = Coord3 { x: 0, y: 0, z: 0 };
 ///      \     / \     /
 ///        \ /     \ /
 /// ```
-fn part_1(tile_directions: &Vec<Vec<Direction>>) -> usize {
+fn part_1(tile_directions: &[Vec<Direction>]) -> usize {
     let mut black_tiles = HashSet::new();
     for directions in tile_directions {
         let tile_loc = directions
@@ -91,23 +96,24 @@ fn part_1(tile_directions: &Vec<Vec<Direction>>) -> usize {
 ///   - White tiles can only flip if they are adjacent to black tiles, therefore on each iteration
 ///     all tiles > 1 tile away from any black tile is assumed to remain white.
 ///
+/// This is a cellular automaton on a hexagonal grid (black = active) with the life-like rule
+/// `B2/S12`, so can be simulated with `automaton::Automaton` by giving the cube coordinates their
+/// hexagonal neighbourhood.
+///
 /// ## High-level algorithm
 ///
 /// 1. Compute starting state using a modified version of the algorithm in `part_1`.
-///     - Track white tiles?
-/// 2. For each day (100 in this case):
-///     - Initialise *empty* set of black tiles
-///     - Initialise *empty* set of white tiles *to check*
-///     - Apply rule 1 to black tiles to partially build black tiles set and fully build white
-///     tiles set:
-///         - Insert into black tile set if rule **is not** matched (remains black)
-///         - Insert any adjacent white tiles into white tiles set
-///     - Apply rule 2 to white tiles set
-///         - Insert into black tile set if rule **is** matched (flipped to black)
-///     - Apply the changes by swapping the previous iteration black tile set with the current one
-///         - TODO: Can this be avoided by tracking a diff rather than complete state?
-/// 3. Return the length of the black tile set
-fn part_2(tile_directions: &Vec<Vec<Direction>>) -> usize {
+/// 2. Step the automaton 100 times (days)
+/// 3. Return the number of black tiles
+fn part_2(tile_directions: &[Vec<Direction>]) -> usize {
+    exhibit(tile_directions, &mut Recording::disabled())
+}
+
+/// The 100 day exhibit of `part_2`, recording the floor at the start of each day.
+///
+/// The floor is not a `grid::Grid`: it is hexagonal, and grows without bound in every direction,
+/// so it stays a set of black tiles. `frame` draws it onto a `Grid` only to be shown.
+fn exhibit(tile_directions: &[Vec<Direction>], recording: &mut Recording) -> usize {
     // Build initial state via part 1 algorithm
     let mut black_tiles = HashSet::new();
     tile_directions
@@ -115,7 +121,7 @@ fn part_2(tile_directions: &Vec<Vec<Direction>>) -> usize {
         .map(|directions| {
             directions
                 .iter()
-                .fold(REFERENCE_TILE, |loc, dir| loc.apply(&dir))
+                .fold(REFERENCE_TILE, |loc, dir| loc.apply(dir))
         })
         .for_each(|tile_loc| {
             if black_tiles.contains(&tile_loc) {
@@ -127,54 +133,68 @@ fn part_2(tile_directions: &Vec<Vec<Direction>>) -> usize {
             }
         });
 
-    // TODO: This can definitely be optimised to reduce the amount of copying/moving around that
-    // takes place. That said, both part_1 and part_2 complete in ~0.1s on my machine so I'll leave
-    // at that for now...
+    let mut automaton = Automaton::new(black_tiles, Rule::new(&[2], &[1, 2]));
+    recording.record(|| frame(automaton.active()));
     for _ in 0..100 {
-        let mut next_black = HashSet::new();
-        let mut current_white = HashSet::new();
-        for tile_loc in &black_tiles {
-            let (black_adj, white_adj): (Vec<Coord3>, Vec<Coord3>) = tile_loc
-                .iter_adjacent()
-                .partition(|loc| black_tiles.contains(loc));
-            if !(black_adj.len() == 0 || black_adj.len() > 2) {
-                next_black.insert(*tile_loc);
-            }
-            current_white.extend(white_adj);
-        }
-        for tile_loc in current_white {
-            if tile_loc
-                .iter_adjacent()
-                .filter(|loc| black_tiles.contains(loc))
-                .count()
-                == 2
-            {
-                next_black.insert(tile_loc);
+        automaton.step();
+        recording.record(|| frame(automaton.active()));
+    }
+    automaton.len()
+}
+
+/// Draw the bounding box of the black tiles (`#`) with white tiles as `.`. Each row of hexagons is
+/// offset by half a tile from the next, so tiles are drawn on every other column:
+///
+/// ```text
+/// . # . .
+///  # . # .
+/// . . # .
+/// ```
+fn frame(black_tiles: &HashSet<Coord3>) -> Frame {
+    // Moving east/west is 2 columns and moving diagonally is 1 row and 1 column
+    let pos = |c: &Coord3| (c.x - c.y, c.z);
+    let cols = black_tiles.iter().map(|c| pos(c).0);
+    let rows = black_tiles.iter().map(|c| pos(c).1);
+    let (min_col, max_col) = (cols.clone().min().unwrap_or(0), cols.max().unwrap_or(0));
+    let (min_row, max_row) = (rows.clone().min().unwrap_or(0), rows.max().unwrap_or(0));
+    let mut frame = Grid::filled(
+        (max_col - min_col + 1) as usize,
+        (max_row - min_row + 1) as usize,
+        BACKGROUND,
+    );
+    for y in 0..frame.height() {
+        for x in 0..frame.width() {
+            // `col + row` is always even for a tile
+            if (x as i32 + min_col + y as i32 + min_row) % 2 == 0 {
+                frame[(x, y)] = '.';
             }
         }
-        black_tiles = next_black
     }
-
-    black_tiles.len()
+    for c in black_tiles {
+        let (col, row) = pos(c);
+        frame[((col - min_col) as usize, (row - min_row) as usize)] = '#';
+    }
+    frame
 }
 
-fn parse_input(input: &str) -> Result<Vec<Vec<Direction>>, ParseError> {
+fn parse_input(input: &str) -> Result<Vec<Vec<Direction>>, Located<ParseError>> {
+    let ctx = Context::new(input);
     input
         .lines()
-        .enumerate()
-        .map(|(lineno, l)| {
+        .map(|l| {
             // TODO: Is there a cleaner way to do this e.g. with a `fold`?
             let mut r = vec![];
             let mut col = 0;
             while col < l.len() {
-                if let Ok(d) = l[col..col + 1].parse::<Direction>() {
+                if let Some(Ok(d)) = l.get(col..col + 1).map(str::parse::<Direction>) {
                     col += 1;
                     r.push(d);
-                } else if let Ok(d) = l[col..col + 2].parse::<Direction>() {
+                } else if let Some(Ok(d)) = l.get(col..col + 2).map(str::parse::<Direction>) {
                     col += 2;
                     r.push(d);
                 } else {
-                    return Err(ParseError::InvalidDirections(lineno, col));
+                    let len = l[col..].chars().next().map_or(0, char::len_utf8);
+                    return Err(ctx.error(&l[col..col + len], ParseError::InvalidDirection));
                 }
             }
 
@@ -183,10 +203,36 @@ fn parse_input(input: &str) -> Result<Vec<Vec<Direction>>, ParseError> {
         .collect()
 }
 
-pub fn run(input: &str) {
-    let tile_directions = parse_input(input).expect("unable to parse input");
-    println!("Part 1: {}", part_1(&tile_directions));
-    println!("Part 2: {}", part_2(&tile_directions));
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Parsed = Vec<Vec<Direction>>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+    type ParseError = Located<ParseError>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1(tile_directions: &Self::Parsed) -> Self::Answer1 {
+        part_1(tile_directions)
+    }
+
+    fn part_2(tile_directions: &Self::Parsed) -> Self::Answer2 {
+        part_2(tile_directions)
+    }
+
+    /// Only the tiles changing in part 2 are interesting to watch.
+    fn visualise(tile_directions: &Self::Parsed, part: Part, recording: &mut Recording) -> bool {
+        match part {
+            Part::One => false,
+            Part::Two => {
+                exhibit(tile_directions, recording);
+                true
+            }
+        }
+    }
 }
 
 str_enum! {
@@ -220,8 +266,10 @@ impl Coord3 {
 
         Coord3 { x, y, z }
     }
+}
 
-    fn iter_adjacent<'a>(&'a self) -> impl Iterator<Item = Coord3> + 'a {
+impl Neighbourhood for Coord3 {
+    fn neighbours(self) -> impl Iterator<Item = Self> {
         [East, Southeast, Southwest, West, Northwest, Northeast]
             .iter()
             .map(move |dir| self.apply(dir))
@@ -229,11 +277,12 @@ impl Coord3 {
 }
 
 #[derive(PartialEq, PartialOrd, Eq, Ord, Debug)]
-enum ParseError {
-    /// `(lineno, col)`
-    InvalidDirections(usize, usize),
+pub enum ParseError {
+    InvalidDirection,
 }
 
+impl_error!(parse ParseError);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -281,6 +330,14 @@ wseweeenwnesenwwwswnew";
         );
     }
 
+    #[test]
+    fn parse_input_invalid() {
+        let e = parse_input("esew\nnwwswx\n").unwrap_err();
+        assert_eq!(e.error, ParseError::InvalidDirection);
+        let l = e.location.unwrap();
+        assert_eq!((l.line, l.col, l.len), (2, 6, 1));
+    }
+
     #[test]
     fn apply_direction() {
         let loc = Coord3 { x: 0, y: 0, z: 0 };
@@ -303,4 +360,30 @@ wseweeenwnesenwwwswnew";
         let tile_directions = parse_input(EXAMPLE_INPUT).unwrap();
         assert_eq!(part_2(&tile_directions), 2208);
     }
+
+    #[test]
+    fn visualise() {
+        let tile_directions = parse_input(EXAMPLE_INPUT).unwrap();
+        let mut recording = Recording::new();
+        assert!(Solver::visualise(&tile_directions, Part::Two, &mut recording));
+        let frames = recording.frames();
+        assert_eq!(frames.len(), 101);
+        let black = |f: &Frame| f.cells().iter().filter(|&&c| c == '#').count();
+        assert_eq!(black(&frames[0]), 10);
+        assert_eq!(black(&frames[1]), 15);
+        assert_eq!(black(&frames[100]), 2208);
+        assert!(!Solver::visualise(&tile_directions, Part::One, &mut recording));
+    }
+
+    #[test]
+    fn hex_frame() {
+        let tiles = [
+            REFERENCE_TILE,
+            REFERENCE_TILE.apply(&East),
+            REFERENCE_TILE.apply(&Southeast),
+            REFERENCE_TILE.apply(&East).apply(&East).apply(&Southeast),
+        ];
+        let expected = "# # . \n # . #";
+        assert_eq!(frame(&tiles.iter().copied().collect()).to_string(), expected);
+    }
 }
@@ -1,4 +1,7 @@
+use crate::parse::{Context, Located, ResultExt};
+use crate::solution::{NotImplemented, Solution};
 use crate::str::split_once;
+
 // TODO: Choose numeric types more thoughtfully?
 const PUBKEY_SUBJECT_NUMBER: u64 = 7;
 
@@ -66,38 +69,50 @@ fn transform_inner(subject: u64, value: u64) -> u64 {
     (value * subject) % 20201227
 }
 
-fn part_2() {
-    todo!()
-}
-
 // TODO: Is `u64` sufficient?
-fn parse_input(input: &str) -> Result<(u64, u64), ParseError> {
-    split_once(input, "\n")
+fn parse_input(input: &str) -> Result<(u64, u64), Located<ParseError>> {
+    let ctx = Context::new(input);
+    let parse_pubkey = |s: &str| {
+        let s = s.trim();
+        s.parse().map_err(ParseError::InvalidPubkey).at(&ctx, s)
+    };
+    let (s1, s2) = split_once(input, "\n")
         .ok_or(ParseError::InvalidInput)
-        .and_then(|(s1, s2)| {
-            Ok((
-                s1.trim()
-                    .parse()
-                    .map_err(|e| ParseError::InvalidPubkey(e))?,
-                s2.trim()
-                    .parse()
-                    .map_err(|e| ParseError::InvalidPubkey(e))?,
-            ))
-        })
+        .at(&ctx, ctx.end())?;
+    Ok((parse_pubkey(s1)?, parse_pubkey(s2)?))
 }
 
-pub fn run(input: &str) {
-    let pubkeys = parse_input(input).expect("unable to parse input");
-    println!("Part 1: {}", part_1(pubkeys));
-    // println!("Part 2: {}", part_2(&parsed));
+pub struct Solver;
+
+impl<'a> Solution<'a> for Solver {
+    type Parsed = (u64, u64);
+    type Answer1 = u64;
+    type Answer2 = NotImplemented;
+    type ParseError = Located<ParseError>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1(pubkeys: &Self::Parsed) -> Self::Answer1 {
+        part_1(*pubkeys)
+    }
+
+    /// There is no part 2 puzzle on day 25 - the final star is awarded for completing every other
+    /// puzzle.
+    fn part_2(_: &Self::Parsed) -> Self::Answer2 {
+        NotImplemented
+    }
 }
 
 #[derive(PartialEq, Eq, Debug)]
-enum ParseError {
+pub enum ParseError {
     InvalidInput,
     InvalidPubkey(std::num::ParseIntError),
 }
 
+impl_error!(parse ParseError);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,283 @@
+//! The symmetries of a square: the dihedral group D4.
+//!
+//! There are 8 ways to orient a square (such as a day 20 tile): 4 rotations, each of which may be
+//! reflected. Each orientation is a `Transform`, which can be applied to grids and positions,
+//! composed with other transforms and inverted:
+//!
+//! ```
+//! use aoc2020::dihedral::{Side, Transform};
+//! use aoc2020::grid::Grid;
+//!
+//! let grid: Grid<char> = "ab\ncd".parse().unwrap();
+//! assert_eq!(Transform::Rotate90.apply(&grid).to_string(), "ca\ndb");
+//! assert_eq!(Transform::Transpose.apply(&grid).to_string(), "ac\nbd");
+//!
+//! // Rotating twice is rotating by 180°, and any reflection undoes itself
+//! let r90 = Transform::Rotate90;
+//! assert_eq!(r90.then(r90), Transform::Rotate180);
+//! assert_eq!(Transform::FlipVertical.inverse(), Transform::FlipVertical);
+//!
+//! // Edges are read clockwise, so reflecting a grid reverses them
+//! assert_eq!(Side::Right.edge(&grid), ['b', 'd']);
+//! let flipped = Transform::FlipHorizontal.apply(&grid);
+//! assert_eq!(Side::Left.edge(&flipped), ['d', 'b']);
+//! ```
+
+use crate::grid::{Grid, Pos};
+use Transform::*;
+
+/// An orientation of a square, relative to its original orientation.
+///
+/// Every transform is a reflection left to right (for the 4 reflections) followed by a number of
+/// clockwise quarter turns.
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Copy)]
+pub enum Transform {
+    Identity,
+    /// A quarter turn clockwise
+    Rotate90,
+    Rotate180,
+    /// A quarter turn anticlockwise
+    Rotate270,
+    /// Reflect left to right, about the vertical axis
+    FlipHorizontal,
+    /// Reflect top to bottom, about the horizontal axis
+    FlipVertical,
+    /// Reflect about the leading diagonal (top left to bottom right), swapping rows and columns
+    Transpose,
+    /// Reflect about the anti-diagonal (top right to bottom left)
+    AntiTranspose,
+}
+
+impl Transform {
+    /// The rotations followed by the reflections, so the first transform in `ALL` which
+    /// satisfies a condition is a rotation if possible.
+    pub const ALL: [Transform; 8] = [
+        Identity,
+        Rotate90,
+        Rotate180,
+        Rotate270,
+        FlipHorizontal,
+        AntiTranspose,
+        FlipVertical,
+        Transpose,
+    ];
+
+    /// `(reflected, quarter_turns)` such that the transform is a reflection left to right if
+    /// `reflected`, followed by `quarter_turns` clockwise quarter turns.
+    fn parts(self) -> (bool, u8) {
+        match self {
+            Identity => (false, 0),
+            Rotate90 => (false, 1),
+            Rotate180 => (false, 2),
+            Rotate270 => (false, 3),
+            FlipHorizontal => (true, 0),
+            AntiTranspose => (true, 1),
+            FlipVertical => (true, 2),
+            Transpose => (true, 3),
+        }
+    }
+
+    fn from_parts(reflected: bool, quarter_turns: u8) -> Self {
+        Self::ALL[reflected as usize * 4 + quarter_turns as usize % 4]
+    }
+
+    pub fn is_reflection(self) -> bool {
+        self.parts().0
+    }
+
+    /// `self` followed by `other`.
+    pub fn then(self, other: Transform) -> Transform {
+        let (f1, r1) = self.parts();
+        let (f2, r2) = other.parts();
+        // Reflecting after turning clockwise is the same as reflecting first and then turning
+        // anticlockwise
+        let r = if f2 { 4 + r2 - r1 } else { r1 + r2 };
+        Transform::from_parts(f1 ^ f2, r)
+    }
+
+    /// The transform which undoes `self`.
+    pub fn inverse(self) -> Transform {
+        match self.parts() {
+            (false, r) => Transform::from_parts(false, 4 - r),
+            // Reflections are their own inverse
+            (true, _) => self,
+        }
+    }
+
+    /// Width and height of a `width`x`height` grid after the transform.
+    pub fn apply_size(self, (width, height): (usize, usize)) -> (usize, usize) {
+        if self.parts().1.is_multiple_of(2) {
+            (width, height)
+        } else {
+            (height, width)
+        }
+    }
+
+    /// Where `pos` in a `width`x`height` grid ends up after the transform.
+    pub fn apply_pos(self, (x, y): Pos, (width, height): (usize, usize)) -> Pos {
+        let (reflected, turns) = self.parts();
+        let (mut x, mut y) = if reflected {
+            (width - 1 - x, y)
+        } else {
+            (x, y)
+        };
+        let (mut width, mut height) = (width, height);
+        for _ in 0..turns {
+            let turned = (height - 1 - y, x);
+            x = turned.0;
+            y = turned.1;
+            std::mem::swap(&mut width, &mut height);
+        }
+        (x, y)
+    }
+
+    /// A copy of `grid` in this orientation.
+    pub fn apply<T: Clone>(self, grid: &Grid<T>) -> Grid<T> {
+        let size = (grid.width(), grid.height());
+        let (width, height) = self.apply_size(size);
+        // Fill the transformed grid by looking up where each cell came from
+        let inverse = self.inverse();
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|pos| grid[inverse.apply_pos(pos, (width, height))].clone())
+            .collect();
+        Grid::new(width, height, cells).unwrap()
+    }
+
+    /// Which side `side` ends up on after the transform.
+    ///
+    /// Edges are read clockwise, so `side.edge(grid)` is the same as
+    /// `self.apply_side(side).edge(&self.apply(grid))`, reversed if `self` is a reflection.
+    pub fn apply_side(self, side: Side) -> Side {
+        let (reflected, turns) = self.parts();
+        let side = match (reflected, side) {
+            (true, Side::Left) => Side::Right,
+            (true, Side::Right) => Side::Left,
+            (_, side) => side,
+        };
+        Side::ALL[(side as usize + turns as usize) % 4]
+    }
+}
+
+/// A side of a square, in clockwise order.
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Copy)]
+pub enum Side {
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+impl Side {
+    pub const ALL: [Side; 4] = [Side::Top, Side::Right, Side::Bottom, Side::Left];
+
+    pub fn opposite(self) -> Side {
+        Side::ALL[(self as usize + 2) % 4]
+    }
+
+    /// The cells along this side of `grid`, read clockwise around the grid: left to right along
+    /// the top, top to bottom down the right, right to left along the bottom and bottom to top up
+    /// the left.
+    ///
+    /// Reading clockwise means the edges of a grid are only reordered when it is rotated, and all
+    /// reversed when it is reflected. The edges of two adjacent grids match if one is the reverse
+    /// of the other.
+    pub fn edge<T: Clone>(self, grid: &Grid<T>) -> Vec<T> {
+        let (width, height) = (grid.width(), grid.height());
+        match self {
+            Side::Top => grid.row(0).map_or(vec![], <[T]>::to_vec),
+            Side::Right => grid.column(width.wrapping_sub(1)).cloned().collect(),
+            Side::Bottom => match height.checked_sub(1).and_then(|y| grid.row(y)) {
+                Some(row) => row.iter().rev().cloned().collect(),
+                None => vec![],
+            },
+            Side::Left => {
+                let mut edge: Vec<T> = grid.column(0).cloned().collect();
+                edge.reverse();
+                edge
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Grid<char> {
+        "abc\ndef\nghi\njkl".parse().unwrap()
+    }
+
+    #[test]
+    fn apply() {
+        let grid = example();
+        let cases = [
+            (Identity, "abc\ndef\nghi\njkl"),
+            (Rotate90, "jgda\nkheb\nlifc"),
+            (Rotate180, "lkj\nihg\nfed\ncba"),
+            (Rotate270, "cfil\nbehk\nadgj"),
+            (FlipHorizontal, "cba\nfed\nihg\nlkj"),
+            (FlipVertical, "jkl\nghi\ndef\nabc"),
+            (Transpose, "adgj\nbehk\ncfil"),
+            (AntiTranspose, "lifc\nkheb\njgda"),
+        ];
+        for (t, expected) in cases.iter() {
+            assert_eq!(t.apply(&grid).to_string(), *expected, "{:?}", t);
+        }
+    }
+
+    #[test]
+    fn compose() {
+        let grid = example();
+        for &a in Transform::ALL.iter() {
+            assert_eq!(a.inverse().apply(&a.apply(&grid)), grid, "{:?}", a);
+            assert_eq!(a.then(a.inverse()), Identity);
+            assert_eq!(a.inverse().then(a), Identity);
+            for &b in Transform::ALL.iter() {
+                assert_eq!(
+                    a.then(b).apply(&grid),
+                    b.apply(&a.apply(&grid)),
+                    "{:?} then {:?}",
+                    a,
+                    b
+                );
+            }
+        }
+        assert_eq!(Rotate90.then(Rotate270), Identity);
+        assert_eq!(FlipHorizontal.then(FlipVertical), Rotate180);
+        assert_eq!(Rotate90.then(FlipHorizontal), Transpose);
+    }
+
+    #[test]
+    fn positions() {
+        for &t in Transform::ALL.iter() {
+            let transformed = t.apply(&example());
+            for (pos, c) in example().iter() {
+                assert_eq!(transformed[t.apply_pos(pos, (3, 4))], *c, "{:?}", t);
+            }
+        }
+    }
+
+    #[test]
+    fn edges() {
+        let grid = example();
+        let edges: Vec<String> = Side::ALL
+            .iter()
+            .map(|s| s.edge(&grid).into_iter().collect())
+            .collect();
+        assert_eq!(edges, ["abc", "cfil", "lkj", "jgda"]);
+        for &t in Transform::ALL.iter() {
+            let transformed = t.apply(&grid);
+            for &side in Side::ALL.iter() {
+                let mut edge = side.edge(&grid);
+                if t.is_reflection() {
+                    edge.reverse();
+                }
+                assert_eq!(t.apply_side(side).edge(&transformed), edge, "{:?}", t);
+            }
+        }
+        assert_eq!(Side::Top.opposite(), Side::Bottom);
+        assert_eq!(Side::Left.opposite(), Side::Right);
+        assert!(Side::Top.edge(&Grid::<char>::filled(0, 0, '.')).is_empty());
+    }
+}
@@ -0,0 +1,199 @@
+//! Crate-wide error type.
+//!
+//! Each day defines its own error types describing exactly what went wrong with its input or
+//! solution (e.g. `d08::ParseError::InvalidOpcode`). These are converted into an `Error` via
+//! `From` (see `impl_error!`) so that callers can handle failures from any day in the same way.
+//!
+//! Parse errors which know where they occurred (see `crate::parse`) are rendered like a compiler
+//! diagnostic when formatted with `{:#}`:
+//!
+//! ```text
+//! unable to parse input: InvalidOpcode
+//!  --> line 3, column 1
+//!   |
+//! 3 | xyz +3
+//!   | ^^^
+//! ```
+
+use crate::parse::{Located, Location};
+use std::fmt;
+use std::io;
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Error {
+    /// The puzzle input could not be parsed
+    Parse {
+        reason: String,
+        location: Option<Location>,
+    },
+    /// The puzzle input was parsed but a part could not be solved for it
+    Solve {
+        reason: String,
+    },
+    Io {
+        kind: io::ErrorKind,
+        reason: String,
+    },
+}
+
+impl Error {
+    pub fn parse(reason: impl fmt::Debug) -> Self {
+        Error::Parse {
+            reason: format!("{:?}", reason),
+            location: None,
+        }
+    }
+
+    pub fn solve(reason: impl fmt::Debug) -> Self {
+        Error::Solve {
+            reason: format!("{:?}", reason),
+        }
+    }
+
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            Error::Parse { location, .. } => location.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse {
+                reason,
+                location: Some(location),
+            } if f.alternate() => {
+                let gutter = " ".repeat(location.line.to_string().len());
+                writeln!(f, "unable to parse input: {}", reason)?;
+                writeln!(f, "{}--> {}", gutter, location)?;
+                writeln!(f, "{} |", gutter)?;
+                writeln!(f, "{} | {}", location.line, location.snippet)?;
+                write!(
+                    f,
+                    "{} | {}{}",
+                    gutter,
+                    " ".repeat(location.col - 1),
+                    "^".repeat(location.len.max(1))
+                )
+            }
+            Error::Parse {
+                reason,
+                location: Some(location),
+            } => write!(f, "unable to parse input at {}: {}", location, reason),
+            Error::Parse { reason, .. } => write!(f, "unable to parse input: {}", reason),
+            Error::Solve { reason } => write!(f, "unable to solve: {}", reason),
+            Error::Io { reason, .. } => write!(f, "unable to read input: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl<E: Into<Error>> From<Located<E>> for Error {
+    fn from(e: Located<E>) -> Self {
+        match e.error.into() {
+            Error::Parse { reason, .. } => Error::Parse {
+                reason,
+                location: e.location,
+            },
+            e => e,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io {
+            kind: e.kind(),
+            reason: e.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::Solution;
+    use crate::{d01, d04, d08, d09};
+
+    #[test]
+    fn from_day_errors() {
+        assert_eq!(
+            Error::from(d08::ParseError::InvalidOpcode),
+            Error::Parse {
+                reason: "InvalidOpcode".to_string(),
+                location: None
+            }
+        );
+        assert_eq!(
+            Error::from(d08::Error::ExpectedInfiniteLoop),
+            Error::Solve {
+                reason: "ExpectedInfiniteLoop".to_string()
+            }
+        );
+        assert_eq!(
+            Error::from(d08::Solver::parse("nop +0\nxyz +3").unwrap_err()).location(),
+            Some(&Location {
+                line: 2,
+                col: 1,
+                len: 3,
+                snippet: "xyz +3".to_string()
+            })
+        );
+        assert!(matches!(
+            Error::from(d09::Error::NotFound),
+            Error::Solve { .. }
+        ));
+        assert!(matches!(
+            Error::from(
+                "x".parse::<i32>()
+                    .map_err(d01::ParseError::InvalidEntry)
+                    .unwrap_err()
+            ),
+            Error::Parse { .. }
+        ));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            Error::from(d04::ParseError::InvalidKV).to_string(),
+            "unable to parse input: InvalidKV"
+        );
+        assert_eq!(
+            Error::from(d08::Solver::parse("nop +0\nxyz +3").unwrap_err()).to_string(),
+            "unable to parse input at line 2, column 1: InvalidOpcode"
+        );
+        assert_eq!(
+            Error::from(d08::Error::ProgramFixNotFound).to_string(),
+            "unable to solve: ProgramFixNotFound"
+        );
+        assert_eq!(
+            Error::from(io::Error::new(io::ErrorKind::NotFound, "d01.txt not found")).to_string(),
+            "unable to read input: d01.txt not found"
+        );
+    }
+
+    #[test]
+    fn display_diagnostic() {
+        let input = "nop +0\n".repeat(9) + "acc +1\njmp +x";
+        let e = Error::from(d08::Solver::parse(&input).unwrap_err());
+        assert_eq!(
+            format!("{:#}", e),
+            "unable to parse input: InvalidInteger(ParseIntError { kind: InvalidDigit })
+  --> line 11, column 5
+   |
+11 | jmp +x
+   |     ^^
+"
+            .trim_end()
+        );
+        // Errors without a location are rendered as normal
+        assert_eq!(
+            format!("{:#}", Error::from(d04::ParseError::InvalidKV)),
+            "unable to parse input: InvalidKV"
+        );
+    }
+}
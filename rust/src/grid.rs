@@ -0,0 +1,413 @@
+//! Fixed size 2D grids, as found in many puzzle inputs.
+//!
+//! Cells are stored contiguously in row-major order and addressed by `(x, y)` positions, where
+//! `x` is the column and `y` is the row, both starting from the top left:
+//!
+//! ```
+//! use aoc2020::grid::{Grid, ADJACENT};
+//!
+//! let grid: Grid<char> = "ab\ncd".parse().unwrap();
+//! assert_eq!(grid[(1, 0)], 'b');
+//! assert_eq!(grid.get((2, 0)), None);
+//! assert_eq!(grid.neighbours((0, 0), &ADJACENT).count(), 3);
+//! assert_eq!(grid.to_string(), "ab\ncd");
+//! ```
+
+use crate::parse::{Context, Located};
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+/// `(x, y)` position of a cell.
+pub type Pos = (usize, usize);
+
+/// Unit vectors `(dx, dy)` to the 4 orthogonally adjacent cells.
+pub const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+/// Unit vectors `(dx, dy)` to all 8 adjacent cells (including diagonals).
+pub const ADJACENT: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// A value which is represented by a single character in puzzle inputs. Implemented for all
+/// `char_enum!` types.
+pub trait Cell: Sized {
+    fn from_char(c: char) -> Option<Self>;
+
+    fn to_char(&self) -> char;
+}
+
+impl Cell for char {
+    fn from_char(c: char) -> Option<Self> {
+        Some(c)
+    }
+
+    fn to_char(&self) -> char {
+        *self
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// Grid of the given dimensions from `cells` in row-major order, or `None` if the number of
+    /// cells does not match the dimensions.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Option<Self> {
+        if width.checked_mul(height)? != cells.len() {
+            return None;
+        }
+        Some(Grid {
+            cells,
+            width,
+            height,
+        })
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// All cells in row-major order.
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn cells_mut(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+
+    /// Index of `pos` in `cells`, or `None` if it is out of bounds.
+    pub fn index_of(&self, (x, y): Pos) -> Option<usize> {
+        if x < self.width && y < self.height {
+            Some(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// Position of the cell at `index` in `cells`, or `None` if it is out of bounds.
+    pub fn pos_of(&self, index: usize) -> Option<Pos> {
+        if index < self.cells.len() {
+            Some((index % self.width, index / self.width))
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.index_of(pos).map(move |i| &mut self.cells[i])
+    }
+
+    /// Position reached by moving `n` steps in direction `(dx, dy)` from `pos`, or `None` if it
+    /// is out of bounds.
+    pub fn step(&self, (x, y): Pos, (dx, dy): (isize, isize), n: usize) -> Option<Pos> {
+        let n = n as isize;
+        let x = x.checked_add_signed(dx.checked_mul(n)?)?;
+        let y = y.checked_add_signed(dy.checked_mul(n)?)?;
+        if x < self.width && y < self.height {
+            Some((x, y))
+        } else {
+            None
+        }
+    }
+
+    /// All cells with their positions in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> + '_ {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, c)| ((i % width, i / width), c))
+    }
+
+    /// Cells one step away from `pos` in each of `dirs` which are within bounds e.g.
+    /// `ORTHOGONAL` or `ADJACENT`.
+    pub fn neighbours<'a>(
+        &'a self,
+        pos: Pos,
+        dirs: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = (Pos, &'a T)> + 'a {
+        dirs.iter()
+            .filter_map(move |&dir| self.step(pos, dir, 1))
+            .map(move |p| (p, &self[p]))
+    }
+
+    /// Cells along the line of sight from `pos` (exclusive) in direction `dir`, until the edge
+    /// of the grid is reached.
+    pub fn ray(&self, pos: Pos, dir: (isize, isize)) -> impl Iterator<Item = (Pos, &T)> + '_ {
+        (1..)
+            .map(move |n| self.step(pos, dir, n))
+            .take_while(Option::is_some)
+            .map(move |p| {
+                let p = p.unwrap(); // Checked by `take_while`
+                (p, &self[p])
+            })
+    }
+
+    /// Row `y`, or `None` if it is out of bounds.
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        if y < self.height {
+            Some(&self.cells[y * self.width..(y + 1) * self.width])
+        } else {
+            None
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        (0..self.height).map(move |y| self.row(y).unwrap())
+    }
+
+    /// Cells in column `x` from top to bottom. Empty if `x` is out of bounds.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> + '_ {
+        let height = if x < self.width { self.height } else { 0 };
+        self.cells
+            .iter()
+            .skip(x)
+            .step_by(self.width.max(1))
+            .take(height)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T> + '_> + '_ {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// Grid of the same dimensions with `f` applied to each cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl<T: Cell> Grid<T> {
+    /// Parse a grid from `s`, one row per line, reporting errors relative to `ctx`. Use this
+    /// instead of `FromStr` when `s` is part of a larger input.
+    pub fn parse(ctx: &Context<'_>, s: &str) -> Result<Self, Located<ParseError>> {
+        let width = s.lines().next().map_or(0, |l| l.chars().count());
+        if width == 0 {
+            return Err(ctx.error(s, ParseError::EmptyInput));
+        }
+        let mut cells = Vec::with_capacity(width * s.lines().count());
+        let mut height = 0;
+        for l in s.lines() {
+            let row_start = cells.len();
+            for (i, c) in l.char_indices() {
+                let cell = T::from_char(c).ok_or_else(|| {
+                    ctx.error(&l[i..i + c.len_utf8()], ParseError::InvalidCharacter(c))
+                })?;
+                cells.push(cell);
+            }
+            if cells.len() - row_start != width {
+                return Err(ctx.error(l, ParseError::MalformedGrid));
+            }
+            height += 1;
+        }
+        Ok(Grid {
+            cells,
+            width,
+            height,
+        })
+    }
+}
+
+impl<T: Cell> FromStr for Grid<T> {
+    type Err = Located<ParseError>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(&Context::new(s), s)
+    }
+}
+
+impl<T: Cell> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for c in row {
+                write!(f, "{}", c.to_char())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &Self::Output {
+        match self.index_of(pos) {
+            Some(i) => &self.cells[i],
+            None => panic!(
+                "position {:?} out of bounds for grid of size {}x{}",
+                pos, self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut Self::Output {
+        match self.index_of(pos) {
+            Some(i) => &mut self.cells[i],
+            None => panic!(
+                "position {:?} out of bounds for grid of size {}x{}",
+                pos, self.width, self.height
+            ),
+        }
+    }
+}
+
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug)]
+pub enum ParseError {
+    EmptyInput,
+    /// Rows were not all the same width
+    MalformedGrid,
+    InvalidCharacter(char),
+}
+
+impl_error!(parse ParseError);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+abc
+def
+ghi
+jkl";
+
+    fn example() -> Grid<char> {
+        EXAMPLE.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_and_display() {
+        let grid = example();
+        assert_eq!((grid.width(), grid.height()), (3, 4));
+        assert_eq!(grid.cells().iter().collect::<String>(), "abcdefghijkl");
+        assert_eq!(grid.to_string(), EXAMPLE);
+        assert_eq!(
+            Grid::new(3, 4, "abcdefghijkl".chars().collect()),
+            Some(grid)
+        );
+        assert_eq!(Grid::new(3, 3, "abcdefghijkl".chars().collect()), None);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let e = "ab\nc\n".parse::<Grid<char>>().unwrap_err();
+        assert_eq!(e.error, ParseError::MalformedGrid);
+        assert_eq!(e.location.map(|l| (l.line, l.col)), Some((2, 1)));
+        assert_eq!(
+            "".parse::<Grid<char>>().unwrap_err().error,
+            ParseError::EmptyInput
+        );
+
+        char_enum! {
+            Bit {
+                (One, '1'),
+                (Zero, '0'),
+            }
+        }
+        let e = "01\n0x".parse::<Grid<Bit>>().unwrap_err();
+        assert_eq!(e.error, ParseError::InvalidCharacter('x'));
+        assert_eq!(e.location.map(|l| (l.line, l.col)), Some((2, 2)));
+        assert_eq!("01\n10".parse::<Grid<Bit>>().unwrap()[(0, 1)], Bit::One);
+    }
+
+    #[test]
+    fn indexing() {
+        let mut grid = example();
+        assert_eq!(grid[(0, 0)], 'a');
+        assert_eq!(grid[(2, 3)], 'l');
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.get((0, 4)), None);
+        assert_eq!(grid.index_of((1, 2)), Some(7));
+        assert_eq!(grid.pos_of(7), Some((1, 2)));
+        assert_eq!(grid.pos_of(12), None);
+        grid[(1, 1)] = 'E';
+        assert_eq!(grid.row(1), Some(&['d', 'E', 'f'][..]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_out_of_bounds() {
+        let _ = example()[(3, 0)];
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = example();
+        let neighbours = |pos, dirs| {
+            grid.neighbours(pos, dirs)
+                .map(|(_, c)| c)
+                .collect::<String>()
+        };
+        assert_eq!(neighbours((0, 0), &ORTHOGONAL), "bd");
+        assert_eq!(neighbours((0, 0), &ADJACENT), "bde");
+        assert_eq!(neighbours((1, 1), &ORTHOGONAL), "bdfh");
+        assert_eq!(neighbours((1, 1), &ADJACENT), "abcdfghi");
+        assert_eq!(neighbours((2, 3), &ADJACENT), "hik");
+    }
+
+    #[test]
+    fn rays() {
+        let grid = example();
+        let ray = |pos, dir| grid.ray(pos, dir).map(|(_, c)| c).collect::<String>();
+        assert_eq!(ray((0, 0), (1, 1)), "ei");
+        assert_eq!(ray((0, 0), (0, 1)), "dgj");
+        assert_eq!(ray((0, 0), (-1, 0)), "");
+        assert_eq!(ray((2, 3), (-1, -1)), "hd");
+        assert_eq!(grid.ray((1, 3), (0, -1)).last(), Some(((1, 0), &'b')));
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = example();
+        let rows: Vec<String> = grid.rows().map(|r| r.iter().collect()).collect();
+        assert_eq!(rows, ["abc", "def", "ghi", "jkl"]);
+        let cols: Vec<String> = grid.columns().map(|c| c.collect()).collect();
+        assert_eq!(cols, ["adgj", "behk", "cfil"]);
+        assert_eq!(grid.column(3).count(), 0);
+        assert_eq!(grid.row(4), None);
+        assert_eq!(
+            grid.map(|c| c.to_ascii_uppercase()).to_string(),
+            "ABC\nDEF\nGHI\nJKL"
+        );
+    }
+}
@@ -0,0 +1,75 @@
+//! Locating puzzle input files.
+//!
+//! Inputs are stored in a single directory (the *root*) as `dNN.txt`, named after the day's
+//! module e.g. `inputs/d01.txt`. The root defaults to `inputs` and can be overridden with the
+//! `AOC2020_INPUTS` environment variable.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Environment variable used to override the default input root.
+pub const ROOT_ENV_VAR: &str = "AOC2020_INPUTS";
+
+pub const DEFAULT_ROOT: &str = "inputs";
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Inputs {
+    root: PathBuf,
+}
+
+impl Inputs {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Inputs { root: root.into() }
+    }
+
+    /// Inputs rooted at `$AOC2020_INPUTS`, or `DEFAULT_ROOT` (relative to the current directory)
+    /// if it is not set.
+    pub fn from_env() -> Self {
+        Self::new(env::var_os(ROOT_ENV_VAR).unwrap_or_else(|| DEFAULT_ROOT.into()))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Path to the input for `day`, which may not exist.
+    pub fn path(&self, day: u8) -> PathBuf {
+        self.root.join(format!("d{:02}.txt", day))
+    }
+
+    /// Read the input for `day`, returning `None` if it does not exist.
+    pub fn read(&self, day: u8) -> io::Result<Option<String>> {
+        match fs::read_to_string(self.path(day)) {
+            Ok(input) => Ok(Some(input)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn input_paths() {
+        let inputs = Inputs::new("inputs");
+        assert_eq!(inputs.path(1), Path::new("inputs/d01.txt"));
+        assert_eq!(inputs.path(25), Path::new("inputs/d25.txt"));
+    }
+
+    #[test]
+    fn read_inputs() {
+        let root = env::temp_dir().join(format!("aoc2020-inputs-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("d03.txt"), "..#\n").unwrap();
+
+        let inputs = Inputs::new(&root);
+        assert_eq!(inputs.read(3).unwrap(), Some("..#\n".to_string()));
+        assert_eq!(inputs.read(4).unwrap(), None);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
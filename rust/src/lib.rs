@@ -1,27 +1,47 @@
-pub mod d01;
-pub mod d03;
-pub mod d04;
-pub mod d05;
-pub mod d06;
-pub mod d07;
-pub mod d08;
-pub mod d09;
-pub mod d10;
-pub mod d11;
-pub mod d12;
-pub mod d13;
-pub mod d14;
-pub mod d15;
-pub mod d16;
-pub mod d17;
+// `ParseError::Invalid*` variants are used throughout to describe the offending input.
+#![allow(clippy::enum_variant_names)]
 
-mod str;
 #[macro_use]
 mod macros;
-pub mod d18;
-pub mod d19;
-pub mod d20;
-pub mod d21;
-pub mod d22;
-pub mod d23;
-pub mod d24;
+mod str;
+
+pub mod answers;
+pub mod automaton;
+pub mod dihedral;
+pub mod error;
+pub mod grid;
+pub mod inputs;
+pub mod parse;
+pub mod solution;
+pub mod visualise;
+
+pub use error::Error;
+
+// Adding a day module here is all that's required to make it available to the binary and
+// benchmarks (see `days!`).
+days! {
+    d01,
+    d03,
+    d04,
+    d05,
+    d06,
+    d07,
+    d08,
+    d09,
+    d10,
+    d11,
+    d12,
+    d13,
+    d14,
+    d15,
+    d16,
+    d17,
+    d18,
+    d19,
+    d20,
+    d21,
+    d22,
+    d23,
+    d24,
+    d25,
+}
@@ -51,7 +51,8 @@ macro_rules! str_enum {
     }
 }
 
-/// Create enums for `char`s. Useful for parsing puzzle inputs.
+/// Create enums for `char`s. Useful for parsing puzzle inputs. The enum implements
+/// `grid::Cell`, so can be used as the cells of a `grid::Grid`.
 ///
 /// ```
 /// # #[macro_use]
@@ -100,6 +101,20 @@ macro_rules! char_enum {
                 })
             }
         }
+
+        impl $crate::grid::Cell for $name {
+            fn from_char(c: char) -> Option<Self> {
+                $name::new(c).ok()
+            }
+
+            fn to_char(&self) -> char {
+                match self {
+                $(
+                  &$name::$item => $repr,
+                )*
+                }
+            }
+        }
     }
 }
 
@@ -112,3 +127,54 @@ macro_rules! dbg_eprintln {
         std::eprintln!($($rest)*)
     }
 }
+
+/// Declare a public module for each day and register its `Solver` in `DAYS`, the registry of
+/// implemented days. Each module must be named `dNN` (e.g. `d01`) and contain a `Solver`
+/// implementing `crate::solution::Solution`. The day number is derived from the module name.
+///
+/// ```ignore
+/// days! {
+///     d01,
+///     d02,
+/// }
+///
+/// assert_eq!(DAYS[1].day, 2);
+/// assert_eq!(DAYS[1].name, "d02");
+/// ```
+macro_rules! days {
+    ($($day:ident),* $(,)?) => {
+        $(
+            pub mod $day;
+        )*
+
+        /// Registry of all implemented days, in order.
+        pub const DAYS: &[$crate::solution::Day] = &[
+        $(
+            $crate::solution::Day::new::<$day::Solver>(stringify!($day)),
+        )*
+        ];
+    }
+}
+
+/// Implement `From` for `crate::error::Error` for a day's parse or solve error type, using its
+/// `Debug` representation as the reason.
+///
+/// ```ignore
+/// #[derive(Debug)]
+/// pub enum ParseError {
+///     InvalidInput,
+/// }
+///
+/// impl_error!(parse ParseError);
+///
+/// assert_eq!(Error::from(ParseError::InvalidInput), Error::parse(ParseError::InvalidInput));
+/// ```
+macro_rules! impl_error {
+    ($kind:ident $t:ident $(<$lt:lifetime>)?) => {
+        impl$(<$lt>)? From<$t$(<$lt>)?> for $crate::error::Error {
+            fn from(e: $t$(<$lt>)?) -> Self {
+                $crate::error::Error::$kind(e)
+            }
+        }
+    };
+}
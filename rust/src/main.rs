@@ -1,38 +1,305 @@
-use aoc2020::*;
-use std::io::{self, Read};
+mod cli;
+mod output;
+mod repl;
+
+use aoc2020::answers::Answers;
+use aoc2020::inputs::Inputs;
+use aoc2020::solution::{self, Day, Outcome, Part, Report, Status, CALENDAR};
+use aoc2020::visualise::{self, Recording};
+use aoc2020::{Error, DAYS};
+use cli::{Args, Command, USAGE};
+use output::{Format, Render, Summary};
+use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::Path;
+use std::process;
+use std::time::Duration;
 
 fn main() {
-    let day = std::env::args().nth(1).expect("must provide a day e.g. 1");
-    // TODO: Macro for this?
-    let run = match day.as_ref() {
-        "1" => d01::run,
-        "3" => d03::run,
-        "4" => d04::run,
-        "5" => d05::run,
-        "6" => d06::run,
-        "7" => d07::run,
-        "8" => d08::run,
-        "9" => d09::run,
-        "10" => d10::run,
-        "11" => d11::run,
-        "12" => d12::run,
-        "13" => d13::run,
-        "14" => d14::run,
-        "15" => d15::run,
-        "16" => d16::run,
-        "17" => d17::run,
-        "18" => d18::run,
-        "19" => d19::run,
-        "20" => d20::run,
-        "21" => d21::run,
-        "22" => d22::run,
-        "23" => d23::run,
-        "24" => d24::run,
-        _ => panic!("must provide a valid day that has been implemented e.g. 1"),
+    let args = Args::parse(std::env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("error: {}\n\n{}", e, USAGE);
+        process::exit(2);
+    });
+
+    let ok = match args.command {
+        Command::Help => {
+            print!("{}", USAGE);
+            true
+        }
+        Command::List => {
+            list();
+            true
+        }
+        Command::Run => run(&args),
+        Command::Bench => bench(&args),
+        Command::Check => check(&args),
+        Command::Visualise => visualise(&args),
+        Command::Repl => repl(&args),
     };
+    if !ok {
+        process::exit(1);
+    }
+}
 
+fn list() {
+    for day in CALENDAR {
+        let parts = match solution::find(DAYS, day) {
+            Some(d) => d
+                .implemented
+                .iter()
+                .zip(&["part 1", "part 2"])
+                .filter(|(&implemented, _)| implemented)
+                .map(|(_, &part)| part)
+                .collect::<Vec<_>>()
+                .join(", "),
+            None => "not implemented".to_string(),
+        };
+        println!("Day {:>2}: {}", day, parts);
+    }
+}
+
+/// Read the puzzle input for `day` from the `--input` file if given, otherwise from the input
+/// root (see `Inputs`). Falls back to stdin when running a single day without an input file.
+fn read_input(args: &Args, day: &Day) -> Result<String, Error> {
+    let inputs = match &args.input {
+        Some(path) if path.is_dir() => Inputs::new(path),
+        Some(path) => return Ok(fs::read_to_string(path)?),
+        None => Inputs::from_env(),
+    };
+    if let Some(input) = inputs.read(day.day)? {
+        return Ok(input);
+    }
+    if args.days.len() > 1 {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} not found", inputs.path(day.day).display()),
+        )
+        .into());
+    }
     let mut input = String::new();
-    io::stdin().read_to_string(&mut input).unwrap();
+    io::stdin().read_to_string(&mut input)?;
+    Ok(input)
+}
+
+/// Solve the selected parts of `day`.
+fn solve(args: &Args, day: u8) -> Result<Report, Error> {
+    match solution::find(DAYS, day) {
+        Some(day) => day.solve(&read_input(args, day)?, &args.parts),
+        None => Ok(Report::not_implemented(&args.parts)),
+    }
+}
+
+fn run(args: &Args) -> bool {
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    let mut ok = true;
+    let mut reports = vec![];
+    for &day in &args.days {
+        match solve(args, day) {
+            Ok(report) => {
+                for outcome in &report.outcomes {
+                    if let Status::Failed(e) = &outcome.status {
+                        ok = false;
+                        if args.format != Format::Text {
+                            eprintln!("day {} part {}: {}", day, outcome.part, e);
+                        }
+                    }
+                }
+                if args.format == Format::Text {
+                    output::write_text(&mut stdout, day, &report).unwrap();
+                }
+                reports.push((day, report));
+            }
+            Err(e) => {
+                eprintln!("day {}: {:#}", day, e);
+                ok = false;
+            }
+        }
+    }
+    match args.format {
+        Format::Text if args.timings => {
+            writeln!(stdout).unwrap();
+            output::write_timings(&mut stdout, &reports).unwrap();
+        }
+        Format::Text => {}
+        Format::Json => output::write_json(&mut stdout, output::records(&reports)).unwrap(),
+        Format::Csv => output::write_csv(&mut stdout, output::records(&reports)).unwrap(),
+    }
+    ok
+}
+
+fn bench(args: &Args) -> bool {
+    let mut ok = true;
+    for &day in &args.days {
+        let day = match solution::find(DAYS, day) {
+            Some(day) => day,
+            None => {
+                println!("Day {:>2}: not implemented", day);
+                continue;
+            }
+        };
+        let input = match read_input(args, day) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("day {}: {:#}", day.day, e);
+                ok = false;
+                continue;
+            }
+        };
+        let mut total = Duration::default();
+        let mut completed = 0;
+        for _ in 0..args.iterations {
+            match day.solve(&input, &args.parts) {
+                Ok(report) => {
+                    total += report.total();
+                    completed += 1;
+                }
+                Err(e) => {
+                    eprintln!("day {}: {:#}", day.day, e);
+                    ok = false;
+                    break;
+                }
+            }
+        }
+        // A day which failed to solve has no meaningful timing
+        if completed < args.iterations {
+            continue;
+        }
+        println!(
+            "Day {:>2}: {:?} (mean of {} iterations)",
+            day.day,
+            total.div_f64(completed as f64),
+            completed
+        );
+    }
+    ok
+}
+
+fn load_answers(path: &Path) -> Result<Answers, String> {
+    let contents = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    contents.parse().map_err(|e| format!("{:?}", e))
+}
+
+fn check(args: &Args) -> bool {
+    let answers = match load_answers(&args.answers) {
+        Ok(answers) => answers,
+        Err(e) => {
+            eprintln!("unable to load {}: {}", args.answers.display(), e);
+            return false;
+        }
+    };
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    let mut summary = Summary::default();
+    for &day in &args.days {
+        let report = match solve(args, day) {
+            Ok(report) => report,
+            Err(e) => {
+                eprintln!("day {}: {:#}", day, e);
+                summary.errors += 1;
+                continue;
+            }
+        };
+        for Outcome { part, status, .. } in report.outcomes {
+            let verdict = answers.verify(day, part, &status);
+            output::write_verdict(&mut stdout, day, part, &verdict).unwrap();
+            summary.add(&verdict);
+        }
+    }
+    writeln!(stdout, "\n{}", summary).unwrap();
+    summary.failed == 0 && summary.errors == 0
+}
+
+/// Record the generations of each selected part of each day and render them.
+fn visualise(args: &Args) -> bool {
+    let mut ok = true;
+    for &day in &args.days {
+        let day = match solution::find(DAYS, day) {
+            Some(day) => day,
+            None => {
+                eprintln!("day {} is not implemented", day);
+                ok = false;
+                continue;
+            }
+        };
+        let input = match read_input(args, day) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("day {}: {:#}", day.day, e);
+                ok = false;
+                continue;
+            }
+        };
+        for &part in &args.parts {
+            let mut recording = Recording::new();
+            match day.visualise(&input, part, &mut recording) {
+                Ok(true) => {}
+                Ok(false) => {
+                    eprintln!("day {} part {} cannot be visualised", day.day, part);
+                    ok = false;
+                    continue;
+                }
+                Err(e) => {
+                    eprintln!("day {}: {:#}", day.day, e);
+                    ok = false;
+                    break;
+                }
+            }
+            if let Err(e) = render(args, day.day, part, &recording) {
+                eprintln!("day {} part {}: {}", day.day, part, e);
+                ok = false;
+            }
+        }
+    }
+    ok
+}
+
+/// Evaluate day 18 expressions read from stdin, prompting for each if stdin is a terminal.
+fn repl(args: &Args) -> bool {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let prompt = stdin.is_terminal();
+    match repl::run(stdin.lock(), &mut stdout.lock(), args.precedence, prompt) {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("repl: {}", e);
+            false
+        }
+    }
+}
 
-    run(&input);
+fn render(args: &Args, day: u8, part: Part, recording: &Recording) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    let frames = recording.frames();
+    match args.render {
+        Render::Text => {
+            writeln!(stdout, "Day {:>2} part {}", day, part)?;
+            visualise::write_text(&mut stdout, frames)
+        }
+        Render::Replay => visualise::replay(&mut stdout, frames, args.delay),
+        Render::Image(format) => {
+            let prefix = format!("d{:02}_part{}", day, part);
+            let paths = visualise::write_images(
+                &args.output,
+                &prefix,
+                frames,
+                format,
+                visualise::default_palette,
+                args.scale,
+            )?;
+            writeln!(
+                stdout,
+                "Day {:>2} part {}: wrote {} images to {}",
+                day,
+                part,
+                paths.len(),
+                args.output.display()
+            )
+        }
+    }?;
+    for note in recording.notes() {
+        writeln!(stdout, "{}", note)?;
+    }
+    Ok(())
 }
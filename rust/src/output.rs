@@ -0,0 +1,396 @@
+//! Output formats for the `aoc2020` binary.
+
+use aoc2020::answers::Verdict;
+use aoc2020::solution::{Part, Report, Status};
+use aoc2020::visualise::ImageFormat;
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::time::Duration;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Format {
+    /// Human readable answers, optionally followed by a table of timings
+    Text,
+    /// A JSON array of records
+    Json,
+    /// CSV records with a header row
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err("Was not Format"),
+        }
+    }
+}
+
+/// How the frames recorded by `visualise` are output.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Render {
+    /// Every frame printed one after another
+    Text,
+    /// An animation in the terminal
+    Replay,
+    /// A sequence of image files
+    Image(ImageFormat),
+}
+
+impl FromStr for Render {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Render::Text),
+            "replay" => Ok(Render::Replay),
+            _ => s.parse().map(Render::Image).map_err(|_| "Was not Render"),
+        }
+    }
+}
+
+/// The answer to and time taken by a single part which was executed.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Record<'a> {
+    pub day: u8,
+    pub part: Part,
+    pub answer: &'a str,
+    pub duration: Duration,
+}
+
+/// Records for each part of each report which was solved.
+pub fn records(reports: &[(u8, Report)]) -> impl Iterator<Item = Record<'_>> {
+    reports.iter().flat_map(|(day, report)| {
+        report.outcomes.iter().filter_map(move |o| match &o.status {
+            Status::Solved(answer) => Some(Record {
+                day: *day,
+                part: o.part,
+                answer,
+                duration: o.duration,
+            }),
+            Status::Failed(_) | Status::NotImplemented => None,
+        })
+    })
+}
+
+/// Print the answers to each part of a single day.
+pub fn write_text(w: &mut impl Write, day: u8, report: &Report) -> io::Result<()> {
+    writeln!(w, "Day {}", day)?;
+    for outcome in &report.outcomes {
+        writeln!(w, "    Part {}: {}", outcome.part, outcome.status)?;
+    }
+    Ok(())
+}
+
+pub fn write_json<'a>(
+    w: &mut impl Write,
+    records: impl Iterator<Item = Record<'a>>,
+) -> io::Result<()> {
+    write!(w, "[")?;
+    for (i, r) in records.enumerate() {
+        if i > 0 {
+            write!(w, ",")?;
+        }
+        write!(
+            w,
+            "\n  {{\"day\": {}, \"part\": {}, \"answer\": \"{}\", \"duration_ns\": {}}}",
+            r.day,
+            r.part,
+            json_escape(r.answer),
+            r.duration.as_nanos()
+        )?;
+    }
+    writeln!(w, "\n]")
+}
+
+pub fn write_csv<'a>(
+    w: &mut impl Write,
+    records: impl Iterator<Item = Record<'a>>,
+) -> io::Result<()> {
+    writeln!(w, "day,part,answer,duration_ns")?;
+    for r in records {
+        writeln!(
+            w,
+            "{},{},{},{}",
+            r.day,
+            r.part,
+            csv_escape(r.answer),
+            r.duration.as_nanos()
+        )?;
+    }
+    Ok(())
+}
+
+/// Print an aligned table of the time taken by each stage of each day, followed by the total
+/// time taken by each stage across all days.
+pub fn write_timings(w: &mut impl Write, reports: &[(u8, Report)]) -> io::Result<()> {
+    const HEADER: [&str; 5] = ["Day", "Parse", "Part 1", "Part 2", "Total"];
+    let fmt = |d: Option<Duration>| d.map_or("-".to_string(), |d| format!("{:.2?}", d));
+
+    let mut rows: Vec<[String; 5]> = reports
+        .iter()
+        .map(|(day, report)| {
+            [
+                day.to_string(),
+                fmt(Some(report.parse)),
+                fmt(report.duration(Part::One)),
+                fmt(report.duration(Part::Two)),
+                fmt(Some(report.total())),
+            ]
+        })
+        .collect();
+    let sum = |f: &dyn Fn(&Report) -> Duration| reports.iter().map(|(_, r)| f(r)).sum();
+    rows.push([
+        "Total".to_string(),
+        fmt(Some(sum(&|r| r.parse))),
+        fmt(Some(sum(&|r| r.duration(Part::One).unwrap_or_default()))),
+        fmt(Some(sum(&|r| r.duration(Part::Two).unwrap_or_default()))),
+        fmt(Some(sum(&Report::total))),
+    ]);
+
+    let mut widths = HEADER.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    let separator = widths.map(|w| "-".repeat(w));
+    let mut write_row = |cells: [&str; 5]| {
+        let line = cells
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, &width))| match i {
+                0 => format!("{:<width$}", cell, width = width),
+                _ => format!("{:>width$}", cell, width = width),
+            })
+            .collect::<Vec<_>>()
+            .join("  ");
+        writeln!(w, "{}", line.trim_end())
+    };
+    write_row(HEADER)?;
+    write_row(separator.each_ref().map(String::as_str))?;
+    for (i, row) in rows.iter().enumerate() {
+        if i == rows.len() - 1 {
+            write_row(separator.each_ref().map(String::as_str))?;
+        }
+        write_row(row.each_ref().map(String::as_str))?;
+    }
+    Ok(())
+}
+
+/// Print the verdict for a single part, followed by the expected and actual answers with the
+/// first difference marked if it failed e.g.
+///
+/// ```text
+/// Day 21 part 2: FAIL
+///     expected: mxmxvkd,sqjhc,fvjkl
+///     actual:   mxmxvkd,sqjhd,fvjkl
+///                           ^
+/// ```
+pub fn write_verdict(w: &mut impl Write, day: u8, part: Part, verdict: &Verdict) -> io::Result<()> {
+    let label = match verdict {
+        Verdict::Pass => "ok",
+        Verdict::Fail { .. } => "FAIL",
+        Verdict::Missing => "missing",
+        Verdict::Skipped => "skipped",
+        Verdict::Error(_) => "ERROR",
+    };
+    writeln!(w, "Day {:>2} part {}: {}", day, part, label)?;
+    if let Verdict::Error(e) = verdict {
+        writeln!(w, "    {}", e)?;
+    }
+    if let (Verdict::Fail { expected, actual }, Some(i)) = (verdict, verdict.mismatch()) {
+        writeln!(w, "    expected: {}", expected)?;
+        writeln!(w, "    actual:   {}", actual)?;
+        writeln!(w, "              {}^", " ".repeat(i))?;
+    }
+    Ok(())
+}
+
+/// Counts of each verdict from a `check` run.
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct Summary {
+    pub passed: usize,
+    pub failed: usize,
+    pub missing: usize,
+    pub skipped: usize,
+    /// Days which could not be checked because their input could not be read or parsed, and
+    /// parts which could not be solved
+    pub errors: usize,
+}
+
+impl Summary {
+    pub fn add(&mut self, verdict: &Verdict) {
+        match verdict {
+            Verdict::Pass => self.passed += 1,
+            Verdict::Fail { .. } => self.failed += 1,
+            Verdict::Missing => self.missing += 1,
+            Verdict::Skipped => self.skipped += 1,
+            Verdict::Error(_) => self.errors += 1,
+        }
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} passed, {} failed, {} missing, {} skipped, {} errors",
+            self.passed, self.failed, self.missing, self.skipped, self.errors
+        )
+    }
+}
+
+fn json_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Quote `s` if it contains any characters with special meaning in CSV (e.g. the comma
+/// separated list answer to day 21 part 2).
+fn csv_escape(s: &str) -> String {
+    if s.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc2020::solution::Outcome;
+
+    fn reports() -> Vec<(u8, Report)> {
+        vec![
+            (
+                21,
+                Report {
+                    parse: Duration::from_nanos(5),
+                    outcomes: vec![
+                        Outcome {
+                            part: Part::One,
+                            status: Status::Solved("5".to_string()),
+                            duration: Duration::from_nanos(10),
+                        },
+                        Outcome {
+                            part: Part::Two,
+                            status: Status::Solved("mxmxvkd,sqjhc,fvjkl".to_string()),
+                            duration: Duration::from_nanos(20),
+                        },
+                    ],
+                },
+            ),
+            (2, Report::not_implemented(&Part::ALL)),
+        ]
+    }
+
+    fn write(f: impl FnOnce(&mut Vec<u8>) -> io::Result<()>) -> String {
+        let mut buf = vec![];
+        f(&mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn records_skip_not_implemented() {
+        let reports = reports();
+        let records: Vec<_> = records(&reports).collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records[1],
+            Record {
+                day: 21,
+                part: Part::Two,
+                answer: "mxmxvkd,sqjhc,fvjkl",
+                duration: Duration::from_nanos(20)
+            }
+        );
+    }
+
+    #[test]
+    fn json() {
+        let reports = reports();
+        assert_eq!(
+            write(|w| write_json(w, records(&reports))),
+            "[
+  {\"day\": 21, \"part\": 1, \"answer\": \"5\", \"duration_ns\": 10},
+  {\"day\": 21, \"part\": 2, \"answer\": \"mxmxvkd,sqjhc,fvjkl\", \"duration_ns\": 20}
+]
+"
+        );
+        assert_eq!(write(|w| write_json(w, records(&[]))), "[\n]\n");
+        assert_eq!(json_escape("a\"b\\c\n"), "a\\\"b\\\\c\\n");
+    }
+
+    #[test]
+    fn csv() {
+        let reports = reports();
+        assert_eq!(
+            write(|w| write_csv(w, records(&reports))),
+            "day,part,answer,duration_ns
+21,1,5,10
+21,2,\"mxmxvkd,sqjhc,fvjkl\",20
+"
+        );
+        assert_eq!(csv_escape("a\"b"), "\"a\"\"b\"");
+    }
+
+    #[test]
+    fn verdicts() {
+        let fail = Verdict::Fail {
+            expected: "mxmxvkd,sqjhc,fvjkl".to_string(),
+            actual: "mxmxvkd,sqjhd,fvjkl".to_string(),
+        };
+        assert_eq!(
+            write(|w| write_verdict(w, 21, Part::Two, &fail)),
+            "Day 21 part 2: FAIL
+    expected: mxmxvkd,sqjhc,fvjkl
+    actual:   mxmxvkd,sqjhd,fvjkl
+                          ^
+"
+        );
+        assert_eq!(
+            write(|w| write_verdict(w, 1, Part::One, &Verdict::Pass)),
+            "Day  1 part 1: ok\n"
+        );
+
+        let mut summary = Summary::default();
+        for verdict in &[Verdict::Pass, Verdict::Pass, fail, Verdict::Missing] {
+            summary.add(verdict);
+        }
+        assert_eq!(
+            summary.to_string(),
+            "2 passed, 1 failed, 1 missing, 0 skipped, 0 errors"
+        );
+    }
+
+    #[test]
+    fn timings() {
+        let reports = reports();
+        assert_eq!(
+            write(|w| write_timings(w, &reports)),
+            "Day     Parse   Part 1   Part 2    Total
+-----  ------  -------  -------  -------
+21     5.00ns  10.00ns  20.00ns  35.00ns
+2      0.00ns        -        -   0.00ns
+-----  ------  -------  -------  -------
+Total  5.00ns  10.00ns  20.00ns  35.00ns
+"
+        );
+    }
+}
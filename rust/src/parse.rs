@@ -0,0 +1,176 @@
+//! Shared context for reporting *where* in the puzzle input a parse error occurred.
+//!
+//! Parsers work with subslices of the input (lines, fields etc) so rather than tracking line and
+//! column numbers by hand, the position of an offending subslice (a *span*) is recovered from its
+//! offset within the original input:
+//!
+//! ```
+//! use aoc2020::parse::{Context, ResultExt};
+//!
+//! let input = "1\n2\nx";
+//! let ctx = Context::new(input);
+//! let err = input
+//!     .lines()
+//!     .map(|l| l.parse::<u32>().at(&ctx, l))
+//!     .collect::<Result<Vec<_>, _>>()
+//!     .unwrap_err();
+//! let location = err.location.unwrap();
+//! assert_eq!((location.line, location.col), (3, 1));
+//! assert_eq!(location.snippet, "x");
+//! ```
+
+use std::fmt;
+
+/// Position of a span within the puzzle input.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Location {
+    /// 1 based line number
+    pub line: usize,
+    /// 1 based column, in characters
+    pub col: usize,
+    /// Length of the span in characters, truncated to the end of the line
+    pub len: usize,
+    /// The whole line containing the start of the span
+    pub snippet: String,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.col)
+    }
+}
+
+/// The puzzle input being parsed.
+#[derive(Debug, Clone, Copy)]
+pub struct Context<'a> {
+    input: &'a str,
+}
+
+impl<'a> Context<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Context { input }
+    }
+
+    /// Location of `span` within the input, or `None` if `span` is not a subslice of the input.
+    pub fn locate(&self, span: &str) -> Option<Location> {
+        let start = (span.as_ptr() as usize).checked_sub(self.input.as_ptr() as usize)?;
+        if start + span.len() > self.input.len() {
+            return None;
+        }
+        let before = &self.input[..start];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line_end = self.input[start..]
+            .find('\n')
+            .map_or(self.input.len(), |i| start + i);
+        let end = (start + span.len()).min(line_end);
+        Some(Location {
+            line: before.matches('\n').count() + 1,
+            col: self.input[line_start..start].chars().count() + 1,
+            len: self.input[start..end].chars().count(),
+            snippet: self.input[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+        })
+    }
+
+    /// Attach the location of `span` to `error`.
+    pub fn error<E>(&self, span: &str, error: E) -> Located<E> {
+        Located {
+            error,
+            location: self.locate(span),
+        }
+    }
+
+    /// An empty span at the end of the input, for errors caused by input ending too early.
+    pub fn end(&self) -> &'a str {
+        &self.input[self.input.len()..]
+    }
+}
+
+/// A parse error with the location in the input at which it occurred, if known.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Located<E> {
+    pub error: E,
+    pub location: Option<Location>,
+}
+
+impl<E> Located<E> {
+    /// An error which cannot be attributed to a particular span of the input.
+    pub fn new(error: E) -> Self {
+        Located {
+            error,
+            location: None,
+        }
+    }
+
+    /// Convert the error, keeping its location.
+    pub fn map<F>(self, f: impl FnOnce(E) -> F) -> Located<F> {
+        Located {
+            error: f(self.error),
+            location: self.location,
+        }
+    }
+}
+
+/// Convenience for attaching locations to the errors of fallible parsing steps.
+pub trait ResultExt<T, E> {
+    /// Attach the location of `span` to the error, if any.
+    fn at(self, ctx: &Context<'_>, span: &str) -> Result<T, Located<E>>;
+
+    /// Wrap the error, if any, without a location.
+    fn unlocated(self) -> Result<T, Located<E>>;
+}
+
+impl<T, E> ResultExt<T, E> for Result<T, E> {
+    fn at(self, ctx: &Context<'_>, span: &str) -> Result<T, Located<E>> {
+        self.map_err(|e| ctx.error(span, e))
+    }
+
+    fn unlocated(self) -> Result<T, Located<E>> {
+        self.map_err(Located::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locate_spans() {
+        let input = "abc\ndéf ghi\n\njkl";
+        let ctx = Context::new(input);
+        let location = |start: usize, len: usize| {
+            let l = ctx.locate(&input[start..start + len]).unwrap();
+            (l.line, l.col, l.len, l.snippet)
+        };
+        assert_eq!(location(0, 3), (1, 1, 3, "abc".to_string()));
+        // Columns are in characters, not bytes
+        assert_eq!(location(9, 3), (2, 5, 3, "déf ghi".to_string()));
+        // Truncated to the end of the line
+        assert_eq!(location(5, 10), (2, 2, 6, "déf ghi".to_string()));
+        assert_eq!(location(13, 0), (3, 1, 0, "".to_string()));
+        assert_eq!(location(input.len(), 0), (4, 4, 0, "jkl".to_string()));
+    }
+
+    #[test]
+    fn locate_outside_input() {
+        let input = String::from("abc");
+        let ctx = Context::new(&input);
+        assert_eq!(ctx.locate("abc"), None);
+        assert_eq!(ctx.error("x", ()), Located::new(()));
+    }
+
+    #[test]
+    fn locate_crlf() {
+        let input = "ab\r\ncd\r\n";
+        let l = Context::new(input).locate(&input[5..6]).unwrap();
+        assert_eq!((l.line, l.col, l.snippet.as_str()), (2, 2, "cd"));
+    }
+
+    #[test]
+    fn end_of_input() {
+        let ctx = Context::new("abc\n");
+        let l = ctx.error(ctx.end(), ()).location;
+        assert_eq!(l.map(|l| (l.line, l.col)), Some((2, 1)));
+    }
+}
@@ -0,0 +1,204 @@
+//! Interactive calculator for day 18 expressions, for exploring how the precedence rules of each
+//! part change the grouping (and value) of an expression.
+
+use aoc2020::d18::{self, OpTable, Value};
+use aoc2020::parse::{Context, Located};
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+const HELP: &str = "\
+Enter an expression to evaluate it, or a command:
+    :part1       All operators have the same precedence, evaluated left to right
+    :part2       Addition and subtraction before multiplication and division
+    :standard    The usual rules of arithmetic
+    :help        Show this message
+    :quit        Exit (as does end of input)
+";
+
+/// The operator precedence rules expressions are parsed with.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Precedence {
+    Part1,
+    Part2,
+    Standard,
+}
+
+impl Precedence {
+    pub fn table(self) -> &'static OpTable {
+        match self {
+            Precedence::Part1 => &OpTable::PART_1,
+            Precedence::Part2 => &OpTable::PART_2,
+            Precedence::Standard => &OpTable::STANDARD,
+        }
+    }
+}
+
+impl FromStr for Precedence {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "part1" | "1" => Ok(Precedence::Part1),
+            "part2" | "2" => Ok(Precedence::Part2),
+            "standard" => Ok(Precedence::Standard),
+            _ => Err("Was not Precedence"),
+        }
+    }
+}
+
+impl fmt::Display for Precedence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Precedence::Part1 => "part1",
+            Precedence::Part2 => "part2",
+            Precedence::Standard => "standard",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Read expressions from `input` a line at a time, writing the value, postfix form and syntax
+/// tree of each to `output`. Lines starting with `:` are commands (see `HELP`).
+///
+/// `prompt` writes a prompt before each line is read, for interactive use.
+pub fn run<R: BufRead, W: Write>(
+    input: R,
+    output: &mut W,
+    mut precedence: Precedence,
+    prompt: bool,
+) -> io::Result<()> {
+    let mut lines = input.lines();
+    loop {
+        if prompt {
+            write!(output, "{}> ", precedence)?;
+            output.flush()?;
+        }
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => break,
+        };
+        let line = line.trim_end();
+        match line.trim_start() {
+            "" => {}
+            ":quit" | ":q" => break,
+            ":help" => write!(output, "{}", HELP)?,
+            command if command.starts_with(':') => match command[1..].parse() {
+                Ok(p) => {
+                    precedence = p;
+                    writeln!(output, "using {} precedence", precedence)?;
+                }
+                Err(_) => writeln!(output, "unknown command `{}`, try :help", command)?,
+            },
+            _ => evaluate(output, line, precedence.table())?,
+        }
+    }
+    if prompt {
+        writeln!(output)?;
+    }
+    Ok(())
+}
+
+fn evaluate<W: Write>(output: &mut W, line: &str, table: &OpTable) -> io::Result<()> {
+    let expr = match d18::parse_line(&Context::new(line), line, table) {
+        Ok(expr) => expr,
+        Err(e) => return write_syntax_error(output, &e),
+    };
+    match expr.evaluate::<Value>() {
+        Ok(value) => writeln!(output, "= {}", value)?,
+        Err(e) => writeln!(output, "error: {:?}", e)?,
+    }
+    writeln!(output, "postfix: {}", expr.postfix())?;
+    writeln!(output, "{}", expr.dump())
+}
+
+/// The offending line with a caret under the span of the error.
+fn write_syntax_error<W: Write>(output: &mut W, e: &Located<d18::ParseError>) -> io::Result<()> {
+    match &e.location {
+        Some(location) => {
+            writeln!(output, "{}", location.snippet)?;
+            writeln!(
+                output,
+                "{}{} {:?}",
+                " ".repeat(location.col - 1),
+                "^".repeat(location.len.max(1)),
+                e.error
+            )
+        }
+        None => writeln!(output, "error: {:?}", e.error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(input: &str, precedence: Precedence) -> String {
+        let mut output = vec![];
+        run(input.as_bytes(), &mut output, precedence, false).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn evaluate_lines() {
+        assert_eq!(
+            session("1 + 2 * 3\n\n:part2\n2 * 3 + 4", Precedence::Standard),
+            "\
+= 7
+postfix: 1 2 3 * +
+Add
+  1
+  Mul
+    2
+    3
+using part2 precedence
+= 14
+postfix: 2 3 4 + *
+Mul
+  2
+  Add
+    3
+    4
+"
+        );
+        assert_eq!(
+            session("1 + 2 * 3\n:quit\n4", Precedence::Part1),
+            "= 9\npostfix: 1 2 + 3 *\nMul\n  Add\n    1\n    2\n  3\n"
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            session("1 + (2 * x)\n(1 + 2\n1 +", Precedence::Part1),
+            "\
+1 + (2 * x)
+         ^ InvalidCharacter('x')
+(1 + 2
+^ UnmatchedParen
+1 +
+   ^ UnexpectedEnd
+"
+        );
+        assert_eq!(
+            session("1 / 0\n:part3", Precedence::Part1),
+            "\
+error: DivisionByZero
+postfix: 1 0 /
+Div
+  1
+  0
+unknown command `:part3`, try :help
+"
+        );
+    }
+
+    #[test]
+    fn parse_precedence() {
+        assert_eq!("part1".parse(), Ok(Precedence::Part1));
+        assert_eq!("2".parse(), Ok(Precedence::Part2));
+        assert_eq!("standard".parse(), Ok(Precedence::Standard));
+        assert!("part3".parse::<Precedence>().is_err());
+        assert_eq!(Precedence::Part2.to_string(), "part2");
+    }
+}
@@ -0,0 +1,461 @@
+//! Common interface implemented by each day's puzzle solution.
+
+use crate::error::Error;
+use crate::visualise::Recording;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// Days in the advent calendar.
+pub const CALENDAR: RangeInclusive<u8> = 1..=25;
+
+/// Each puzzle has two parts, the second unlocked by solving the first.
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Hash, Clone, Copy)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    /// `0` for `Part::One`, `1` for `Part::Two`.
+    pub fn index(self) -> usize {
+        match self {
+            Part::One => 0,
+            Part::Two => 1,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.index() + 1)
+    }
+}
+
+impl FromStr for Part {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err("Was not Part"),
+        }
+    }
+}
+
+/// A solution to a single day's puzzle.
+///
+/// Puzzle input is parsed *once* by `parse` and the result is shared by `part_1` and `part_2`,
+/// allowing each stage to be called independently. `'a` is the lifetime of the puzzle input,
+/// which allows `Parsed` to borrow from it (e.g. `Vec<&'a str>`) rather than copying.
+///
+/// ```
+/// use aoc2020::d01;
+/// use aoc2020::solution::Solution;
+///
+/// let entries = d01::Solver::parse("1721\n979\n366\n299\n675\n1456").unwrap();
+/// assert_eq!(d01::Solver::part_1(&entries), Ok(514579));
+/// assert_eq!(d01::Solver::part_2(&entries), Ok(241861950));
+/// ```
+pub trait Solution<'a> {
+    type Parsed;
+    type Answer1: Answer;
+    type Answer2: Answer;
+    type ParseError: Into<Error>;
+
+    fn parse(input: &'a str) -> Result<Self::Parsed, Self::ParseError>;
+
+    fn part_1(parsed: &Self::Parsed) -> Self::Answer1;
+
+    fn part_2(parsed: &Self::Parsed) -> Self::Answer2;
+
+    /// Record each generation of the simulation performed by `part`, for puzzles which are
+    /// simulations. Returns `false` if `part` cannot be visualised.
+    fn visualise(_parsed: &Self::Parsed, _part: Part, _recording: &mut Recording) -> bool {
+        false
+    }
+}
+
+/// The answer to a single part of a puzzle.
+///
+/// Parts which may fail to find an answer for a (valid) input return `Result<A, E>` where
+/// `E: Into<Error>`.
+pub trait Answer {
+    /// `false` for parts which have not been implemented (see `NotImplemented`).
+    const IMPLEMENTED: bool = true;
+
+    /// The formatted answer, or the reason it could not be found.
+    fn into_answer(self) -> Result<String, Error>;
+}
+
+macro_rules! impl_answer {
+    ($($t:ty),*) => {
+        $(
+            impl Answer for $t {
+                fn into_answer(self) -> Result<String, Error> {
+                    Ok(self.to_string())
+                }
+            }
+        )*
+    }
+}
+
+impl_answer!(i32, i64, u16, u32, u64, usize, String);
+#[cfg(feature = "bigint")]
+impl_answer!(num_bigint::BigInt);
+
+impl<A, E> Answer for Result<A, E>
+where
+    A: Answer,
+    E: Into<Error>,
+{
+    const IMPLEMENTED: bool = A::IMPLEMENTED;
+
+    fn into_answer(self) -> Result<String, Error> {
+        self.map_err(Into::into)?.into_answer()
+    }
+}
+
+/// Answer type for a part which has not been implemented, e.g. day 25 which has no part 2.
+///
+/// `Solution` implementations use this in place of a `todo!()` so that callers can determine
+/// that a part has not been implemented *without* calling it.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct NotImplemented;
+
+impl Answer for NotImplemented {
+    const IMPLEMENTED: bool = false;
+
+    fn into_answer(self) -> Result<String, Error> {
+        Ok(self.to_string())
+    }
+}
+
+impl fmt::Display for NotImplemented {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "not implemented")
+    }
+}
+
+/// Outcome of a single part of a puzzle.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Status {
+    /// Formatted answer
+    Solved(String),
+    Failed(Error),
+    NotImplemented,
+}
+
+impl Status {
+    fn of<A: Answer>(solve: impl FnOnce() -> A) -> Self {
+        if !A::IMPLEMENTED {
+            return Status::NotImplemented;
+        }
+        match solve().into_answer() {
+            Ok(answer) => Status::Solved(answer),
+            Err(e) => Status::Failed(e),
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Solved(answer) => write!(f, "{}", answer),
+            Status::Failed(e) => write!(f, "{}", e),
+            Status::NotImplemented => write!(f, "{}", NotImplemented),
+        }
+    }
+}
+
+/// Outcome of solving one part of a puzzle.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Outcome {
+    pub part: Part,
+    pub status: Status,
+    /// Time taken to solve the part, excluding parsing. Zero if the part is not implemented.
+    pub duration: Duration,
+}
+
+/// Outcomes of solving a single day's puzzle.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Report {
+    /// Time taken to parse the input. Zero if the day is not implemented.
+    pub parse: Duration,
+    pub outcomes: Vec<Outcome>,
+}
+
+impl Report {
+    /// Report for a day which has not been implemented at all.
+    pub fn not_implemented(parts: &[Part]) -> Self {
+        Report {
+            parse: Duration::default(),
+            outcomes: parts
+                .iter()
+                .map(|&part| Outcome {
+                    part,
+                    status: Status::NotImplemented,
+                    duration: Duration::default(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Time taken to solve `part`, if it was solved.
+    pub fn duration(&self, part: Part) -> Option<Duration> {
+        self.outcomes
+            .iter()
+            .find(|o| o.part == part && o.status != Status::NotImplemented)
+            .map(|o| o.duration)
+    }
+
+    /// Total time taken to parse the input and solve each part.
+    pub fn total(&self) -> Duration {
+        self.parse + self.outcomes.iter().map(|o| o.duration).sum::<Duration>()
+    }
+}
+
+/// Parse `input` and solve `parts` of the puzzle using `S`, in the order given, timing each
+/// stage.
+///
+/// Returns `Err` if `input` could not be parsed. Parts which fail are reported as
+/// `Status::Failed` without preventing the remaining parts from being solved.
+pub fn solve<S>(input: &str, parts: &[Part]) -> Result<Report, Error>
+where
+    S: for<'a> Solution<'a>,
+{
+    let (parsed, parse) = timed(|| S::parse(input));
+    let parsed = parsed.map_err(Into::into)?;
+    let outcomes = parts
+        .iter()
+        .map(|&part| {
+            let (status, duration) = match part {
+                Part::One => timed(|| Status::of(|| S::part_1(&parsed))),
+                Part::Two => timed(|| Status::of(|| S::part_2(&parsed))),
+            };
+            Outcome {
+                part,
+                status,
+                duration,
+            }
+        })
+        .collect();
+    Ok(Report { parse, outcomes })
+}
+
+/// Parse `input` and record the simulation performed by `part` of the puzzle using `S`.
+///
+/// Returns `Ok(false)` if `part` cannot be visualised.
+pub fn visualise<S>(input: &str, part: Part, recording: &mut Recording) -> Result<bool, Error>
+where
+    S: for<'a> Solution<'a>,
+{
+    let parsed = S::parse(input).map_err(Into::into)?;
+    Ok(S::visualise(&parsed, part, recording))
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+/// Type erased entry for a single day in the solution registry (see `crate::DAYS`).
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub day: u8,
+    /// Module name e.g. `"d01"`
+    pub name: &'static str,
+    /// Whether `[part_1, part_2]` have been implemented
+    pub implemented: [bool; 2],
+    solve: fn(&str, &[Part]) -> Result<Report, Error>,
+    visualise: fn(&str, Part, &mut Recording) -> Result<bool, Error>,
+}
+
+impl Day {
+    /// Panics if `name` is not of the form `dNN` e.g. `d01`. When used in a `const` context (as
+    /// in `crate::DAYS`) this is a compile time error.
+    pub const fn new<S>(name: &'static str) -> Self
+    where
+        S: for<'a> Solution<'a>,
+    {
+        Day {
+            day: day_number(name),
+            name,
+            implemented: [
+                <<S as Solution<'static>>::Answer1 as Answer>::IMPLEMENTED,
+                <<S as Solution<'static>>::Answer2 as Answer>::IMPLEMENTED,
+            ],
+            solve: solve::<S>,
+            visualise: visualise::<S>,
+        }
+    }
+
+    pub fn is_implemented(&self, part: Part) -> bool {
+        self.implemented[part.index()]
+    }
+
+    /// See `solve`.
+    pub fn solve(&self, input: &str, parts: &[Part]) -> Result<Report, Error> {
+        (self.solve)(input, parts)
+    }
+
+    /// See `visualise`.
+    pub fn visualise(
+        &self,
+        input: &str,
+        part: Part,
+        recording: &mut Recording,
+    ) -> Result<bool, Error> {
+        (self.visualise)(input, part, recording)
+    }
+}
+
+impl fmt::Debug for Day {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Day")
+            .field("day", &self.day)
+            .field("name", &self.name)
+            .field("implemented", &self.implemented)
+            .finish()
+    }
+}
+
+/// Find `day` in `days`, returning `None` if it has not been implemented.
+pub fn find(days: &'static [Day], day: u8) -> Option<&'static Day> {
+    days.iter().find(|d| d.day == day)
+}
+
+/// Extract the day number from a module name of the form `dNN`.
+const fn day_number(name: &str) -> u8 {
+    let b = name.as_bytes();
+    if b.len() != 3 || b[0] != b'd' || !b[1].is_ascii_digit() || !b[2].is_ascii_digit() {
+        panic!("day module names must be of the form dNN e.g. d01");
+    }
+    (b[1] - b'0') * 10 + (b[2] - b'0')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{d01, d25};
+
+    const D01_EXAMPLE_INPUT: &str = "1721\n979\n366\n299\n675\n1456";
+
+    /// Statuses of each part in `report`, ignoring timings.
+    fn statuses(report: Report) -> Vec<(Part, Status)> {
+        report
+            .outcomes
+            .into_iter()
+            .map(|o| (o.part, o.status))
+            .collect()
+    }
+
+    fn solved(part: Part, answer: &str) -> (Part, Status) {
+        (part, Status::Solved(answer.to_string()))
+    }
+
+    #[test]
+    fn solve_example() {
+        assert_eq!(
+            solve::<d01::Solver>(D01_EXAMPLE_INPUT, &Part::ALL).map(statuses),
+            Ok(vec![
+                solved(Part::One, "514579"),
+                solved(Part::Two, "241861950")
+            ])
+        );
+    }
+
+    #[test]
+    fn solve_single_part() {
+        assert_eq!(
+            solve::<d01::Solver>(D01_EXAMPLE_INPUT, &[Part::Two]).map(statuses),
+            Ok(vec![solved(Part::Two, "241861950")])
+        );
+    }
+
+    #[test]
+    fn solve_not_implemented() {
+        let report = solve::<d25::Solver>("5764801\n17807724", &Part::ALL).unwrap();
+        assert!(report.duration(Part::One).is_some());
+        assert_eq!(report.duration(Part::Two), None);
+        assert_eq!(
+            statuses(report),
+            vec![
+                solved(Part::One, "14897079"),
+                (Part::Two, Status::NotImplemented)
+            ]
+        );
+    }
+
+    #[test]
+    fn report_total() {
+        let report = Report {
+            parse: Duration::from_millis(1),
+            outcomes: vec![
+                Outcome {
+                    part: Part::One,
+                    status: Status::Solved("1".to_string()),
+                    duration: Duration::from_millis(2),
+                },
+                Outcome {
+                    part: Part::Two,
+                    status: Status::Solved("2".to_string()),
+                    duration: Duration::from_millis(3),
+                },
+            ],
+        };
+        assert_eq!(report.total(), Duration::from_millis(6));
+        assert_eq!(
+            Report::not_implemented(&Part::ALL).total(),
+            Duration::default()
+        );
+    }
+
+    #[test]
+    fn implemented_parts() {
+        assert_eq!(Day::new::<d01::Solver>("d01").implemented, [true, true]);
+        assert_eq!(Day::new::<d25::Solver>("d25").implemented, [true, false]);
+    }
+
+    #[test]
+    fn day_numbers() {
+        assert_eq!(day_number("d01"), 1);
+        assert_eq!(day_number("d10"), 10);
+        assert_eq!(day_number("d25"), 25);
+    }
+
+    #[test]
+    #[should_panic]
+    fn day_number_invalid() {
+        day_number("day1");
+    }
+
+    #[test]
+    fn solve_invalid_input() {
+        assert!(matches!(
+            solve::<d01::Solver>("1721\nabc", &Part::ALL),
+            Err(Error::Parse { .. })
+        ));
+    }
+
+    #[test]
+    fn solve_failed_part() {
+        // No triples
+        let report = solve::<d01::Solver>("2000\n20", &Part::ALL).unwrap();
+        assert_eq!(
+            statuses(report),
+            vec![
+                solved(Part::One, "40000"),
+                (
+                    Part::Two,
+                    Status::Failed(Error::solve(d01::Error::NotFound))
+                )
+            ]
+        );
+    }
+}
@@ -0,0 +1,344 @@
+//! Recording and exporting the generations of simulations, such as the seating in day 11 or the
+//! Conway cubes of day 17.
+//!
+//! Each generation is captured as a `Frame`, a grid of the characters used to draw it in the
+//! puzzle description. Frames can then be written out as plain text, replayed as an animation in
+//! a terminal, or written as a sequence of PBM/PGM/PPM images (which can be converted into a video
+//! with e.g. `ffmpeg -i frame_%04d.ppm out.mp4`). Notes, such as a legend for the frames, can be
+//! recorded alongside them.
+//!
+//! ```
+//! use aoc2020::visualise::Recording;
+//!
+//! let mut recording = Recording::new();
+//! recording.record(|| "#.\n.#".parse().unwrap());
+//! assert_eq!(recording.frames()[0].to_string(), "#.\n.#");
+//!
+//! // Frames are not rendered at all when recording is disabled
+//! let mut disabled = Recording::disabled();
+//! disabled.record(|| unreachable!());
+//! assert!(disabled.frames().is_empty());
+//! ```
+
+use crate::grid::Grid;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+/// A single generation of a simulation.
+pub type Frame = Grid<char>;
+
+/// Character used to pad frames to a common size.
+pub const BACKGROUND: char = ' ';
+
+/// Frames captured from a simulation, in order.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Recording {
+    frames: Vec<Frame>,
+    notes: Vec<String>,
+    enabled: bool,
+}
+
+impl Recording {
+    pub fn new() -> Self {
+        Recording {
+            frames: vec![],
+            notes: vec![],
+            enabled: true,
+        }
+    }
+
+    /// A recording which discards all frames, used when solving normally.
+    pub fn disabled() -> Self {
+        Recording {
+            frames: vec![],
+            notes: vec![],
+            enabled: false,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Capture the frame rendered by `frame`, which is only called if recording is enabled.
+    pub fn record(&mut self, frame: impl FnOnce() -> Frame) {
+        if self.enabled {
+            self.frames.push(frame());
+        }
+    }
+
+    /// Add the text rendered by `note`, which is only called if recording is enabled.
+    pub fn note(&mut self, note: impl FnOnce() -> String) {
+        if self.enabled {
+            self.notes.push(note());
+        }
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    pub fn notes(&self) -> &[String] {
+        &self.notes
+    }
+}
+
+impl Default for Recording {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Print each frame preceded by its generation number and followed by a blank line.
+pub fn write_text(w: &mut impl Write, frames: &[Frame]) -> io::Result<()> {
+    for (i, frame) in frames.iter().enumerate() {
+        writeln!(w, "Generation {}", i)?;
+        writeln!(w, "{}", frame)?;
+        writeln!(w)?;
+    }
+    Ok(())
+}
+
+/// Animate the frames in a terminal by redrawing the screen every `delay`.
+pub fn replay(w: &mut impl Write, frames: &[Frame], delay: Duration) -> io::Result<()> {
+    for (i, frame) in frames.iter().enumerate() {
+        // Clear the screen and move the cursor to the top left
+        write!(w, "\x1b[2J\x1b[H")?;
+        writeln!(w, "Generation {}/{}", i, frames.len().saturating_sub(1))?;
+        writeln!(w, "{}", frame)?;
+        w.flush()?;
+        thread::sleep(delay);
+    }
+    Ok(())
+}
+
+/// Colours of the characters in frames.
+pub type Palette = fn(char) -> [u8; 3];
+
+/// Dark for "active" characters (`#`), light for "inactive" ones (`.`, `L`), white for the
+/// background, teal for highlighted characters (`O`) and shades of blue for digits.
+pub fn default_palette(c: char) -> [u8; 3] {
+    match c {
+        '#' => [32, 32, 32],
+        'O' => [0, 112, 112],
+        'L' => [160, 160, 160],
+        '.' => [224, 224, 224],
+        BACKGROUND => [255, 255, 255],
+        _ => match c.to_digit(10) {
+            Some(d) => [0, (d * 20) as u8, (75 + d * 20) as u8],
+            None => [255, 0, 0],
+        },
+    }
+}
+
+/// [Netpbm](https://en.wikipedia.org/wiki/Netpbm) image formats.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ImageFormat {
+    /// Black and white
+    Pbm,
+    /// Greyscale
+    Pgm,
+    /// Colour
+    Ppm,
+}
+
+impl ImageFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Pbm => "pbm",
+            ImageFormat::Pgm => "pgm",
+            ImageFormat::Ppm => "ppm",
+        }
+    }
+}
+
+impl FromStr for ImageFormat {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pbm" => Ok(ImageFormat::Pbm),
+            "pgm" => Ok(ImageFormat::Pgm),
+            "ppm" => Ok(ImageFormat::Ppm),
+            _ => Err("Was not ImageFormat"),
+        }
+    }
+}
+
+/// Write `frame` as a binary PBM/PGM/PPM image where each cell is `scale`x`scale` pixels. Cells
+/// are black in a PBM if they would be darker than mid grey in a PGM.
+pub fn write_image(
+    w: &mut impl Write,
+    frame: &Frame,
+    format: ImageFormat,
+    palette: Palette,
+    scale: usize,
+) -> io::Result<()> {
+    let (magic, channels) = match format {
+        ImageFormat::Pbm => ("P4", 1),
+        ImageFormat::Pgm => ("P5", 1),
+        ImageFormat::Ppm => ("P6", 3),
+    };
+    let (width, height) = (frame.width() * scale, frame.height() * scale);
+    write!(w, "{}\n{} {}\n", magic, width, height)?;
+    if format != ImageFormat::Pbm {
+        writeln!(w, "255")?;
+    }
+    let mut line = Vec::with_capacity(width * channels);
+    for row in frame.rows() {
+        line.clear();
+        for &c in row {
+            let rgb = palette(c);
+            let [r, g, b] = rgb.map(u32::from);
+            // ITU-R BT.601 luma
+            let grey = [((299 * r + 587 * g + 114 * b) / 1000) as u8];
+            let pixel: &[u8] = match format {
+                ImageFormat::Pbm | ImageFormat::Pgm => &grey,
+                ImageFormat::Ppm => &rgb,
+            };
+            for _ in 0..scale {
+                line.extend_from_slice(pixel);
+            }
+        }
+        if format == ImageFormat::Pbm {
+            line = pack_bits(&line);
+        }
+        for _ in 0..scale {
+            w.write_all(&line)?;
+        }
+    }
+    Ok(())
+}
+
+/// Pack a row of greyscale pixels into a PBM row: 8 pixels per byte, most significant bit first,
+/// with `1` for black.
+fn pack_bits(grey: &[u8]) -> Vec<u8> {
+    grey.chunks(8)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .filter(|(_, &g)| g < 128)
+                .fold(0, |byte, (i, _)| byte | (0x80 >> i))
+        })
+        .collect()
+}
+
+/// Write each frame to `dir` as `<prefix>_NNNN.<ext>`, returning the paths written. Frames are
+/// centred on a `BACKGROUND` of the size of the largest frame so that every image in the
+/// sequence has the same dimensions.
+pub fn write_images(
+    dir: &Path,
+    prefix: &str,
+    frames: &[Frame],
+    format: ImageFormat,
+    palette: Palette,
+    scale: usize,
+) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+    let width = frames.iter().map(Frame::width).max().unwrap_or(0);
+    let height = frames.iter().map(Frame::height).max().unwrap_or(0);
+    frames
+        .iter()
+        .enumerate()
+        .map(|(i, frame)| {
+            let path = dir.join(format!("{}_{:04}.{}", prefix, i, format.extension()));
+            let mut w = io::BufWriter::new(fs::File::create(&path)?);
+            write_image(&mut w, &pad(frame, width, height), format, palette, scale)?;
+            w.flush()?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/// `frame` centred in a `width`x`height` frame of `BACKGROUND`.
+pub fn pad(frame: &Frame, width: usize, height: usize) -> Frame {
+    let mut padded = Grid::filled(
+        width.max(frame.width()),
+        height.max(frame.height()),
+        BACKGROUND,
+    );
+    let dx = (padded.width() - frame.width()) / 2;
+    let dy = (padded.height() - frame.height()) / 2;
+    for ((x, y), &c) in frame.iter() {
+        padded[(x + dx, y + dy)] = c;
+    }
+    padded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(s: &str) -> Frame {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn text() {
+        let mut buf = vec![];
+        write_text(&mut buf, &[frame("#."), frame(".#")]).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "Generation 0\n#.\n\nGeneration 1\n.#\n\n"
+        );
+    }
+
+    #[test]
+    fn images() {
+        let mut buf = vec![];
+        write_image(
+            &mut buf,
+            &frame("#.\nL#"),
+            ImageFormat::Pgm,
+            default_palette,
+            1,
+        )
+        .unwrap();
+        assert_eq!(buf[..11], *b"P5\n2 2\n255\n");
+        assert_eq!(buf[11..], [32, 224, 160, 32]);
+
+        let mut buf = vec![];
+        write_image(&mut buf, &frame("#."), ImageFormat::Ppm, default_palette, 2).unwrap();
+        assert_eq!(buf[..11], *b"P6\n4 2\n255\n");
+        let row = [32, 32, 32, 32, 32, 32, 224, 224, 224, 224, 224, 224];
+        assert_eq!(buf[11..], [row, row].concat()[..]);
+
+        // Rows are padded to whole bytes
+        let mut buf = vec![];
+        let bits = frame("#.#.O....\n.........");
+        write_image(&mut buf, &bits, ImageFormat::Pbm, default_palette, 1).unwrap();
+        assert_eq!(buf[..7], *b"P4\n9 2\n");
+        assert_eq!(buf[7..], [0b10101000, 0, 0, 0]);
+    }
+
+    #[test]
+    fn write_image_sequence() {
+        let dir = std::env::temp_dir().join(format!("aoc2020-frames-{}", std::process::id()));
+        let paths = write_images(
+            &dir,
+            "d11",
+            &[frame("#"), frame("##\n##\n##")],
+            ImageFormat::Pgm,
+            default_palette,
+            1,
+        )
+        .unwrap();
+        assert_eq!(paths, [dir.join("d11_0000.pgm"), dir.join("d11_0001.pgm")]);
+        // Both padded to 2x3
+        for path in &paths {
+            assert!(fs::read(path).unwrap().starts_with(b"P5\n2 3\n255\n"));
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn pad_frames() {
+        assert_eq!(pad(&frame("#"), 3, 3).to_string(), "   \n # \n   ");
+        assert_eq!(pad(&frame("##"), 1, 1).to_string(), "##");
+    }
+}